@@ -25,6 +25,7 @@ pub struct App {
     redraw_region: Region,
     pending_timeouts: Vec<(TimeoutTag, Duration)>,
     next_input_id: InputId,
+    sprite_sheet_import: Option<(InputId, Vec<u8>)>,
     pub runtime_options: RuntimeOptions,
 }
 
@@ -39,6 +40,7 @@ impl App {
             redraw_region: Region::default(),
             pending_timeouts: Vec::new(),
             next_input_id: InputId::default(),
+            sprite_sheet_import: None,
             runtime_options: RuntimeOptions::default(),
         })
     }
@@ -80,19 +82,44 @@ impl App {
     }
 
     pub fn enqueue_input_number_request(&mut self) -> InputId {
-        let id = self.next_input_id.get_and_increment();
-        let request = IoRequest::InputNumber { id };
-        self.io_requests.push_back(request);
-        id
+        self.enqueue_input_request(|id| IoRequest::InputNumber { id })
     }
 
     pub fn enqueue_input_size_request(&mut self) -> InputId {
+        self.enqueue_input_request(|id| IoRequest::InputSize { id })
+    }
+
+    /// Asks the user for a text (`error` is shown if the previous `text` was rejected).
+    pub fn enqueue_input_text_request(&mut self, text: String, error: Option<String>) -> InputId {
+        self.enqueue_input_request(|id| IoRequest::InputText { id, text, error })
+    }
+
+    fn enqueue_input_request(&mut self, request: impl FnOnce(InputId) -> IoRequest) -> InputId {
+        // A pending sprite sheet import is abandoned by a new input request.
+        self.sprite_sheet_import = None;
+
         let id = self.next_input_id.get_and_increment();
-        let request = IoRequest::InputSize { id };
-        self.io_requests.push_back(request);
+        self.io_requests.push_back(request(id));
         id
     }
 
+    /// Asks the user for the cell size to import the given image as a sprite sheet.
+    ///
+    /// The image is released when the input is canceled or another input is requested.
+    pub fn start_sprite_sheet_import(&mut self, png_data: Vec<u8>) {
+        let id = self.enqueue_input_size_request();
+        self.sprite_sheet_import = Some((id, png_data));
+    }
+
+    pub fn take_sprite_sheet_import(&mut self, id: InputId) -> Option<Vec<u8>> {
+        if self.sprite_sheet_import.as_ref()?.0 != id {
+            return None;
+        }
+        self.sprite_sheet_import
+            .take()
+            .map(|(_, png_data)| png_data)
+    }
+
     pub fn enqueue_io_request(&mut self, request: IoRequest) {
         self.io_requests.push_back(request);
     }
@@ -59,6 +59,7 @@ impl Assets {
             IconId::PenStroke => &self.icons.pen_stroke,
             IconId::PenLine => &self.icons.pen_line,
             IconId::PenRectangle => &self.icons.pen_rectangle,
+            IconId::PenFilledRectangle => &self.icons.pen_filled_rectangle,
             IconId::PenCircle => &self.icons.pen_circle,
             IconId::Bucket => &self.icons.bucket,
             IconId::ScissorRectangle => &self.icons.scissor_rectangle,
@@ -113,6 +114,7 @@ pub enum IconId {
     PenStroke,
     PenLine,
     PenRectangle,
+    PenFilledRectangle,
     PenCircle,
     Bucket,
     ScissorRectangle,
@@ -282,6 +284,7 @@ pub struct Icons {
     pub pen_stroke: Sprite,
     pub pen_line: Sprite,
     pub pen_rectangle: Sprite,
+    pub pen_filled_rectangle: Sprite,
     pub pen_circle: Sprite,
     pub bucket: Sprite,
     pub scissor_rectangle: Sprite,
@@ -332,6 +335,10 @@ impl Icons {
             pen_line: decode_sprite(include_bytes!("../assets/icon-pen-line.png")).or_fail()?,
             pen_rectangle: decode_sprite(include_bytes!("../assets/icon-pen-rectangle.png"))
                 .or_fail()?,
+            pen_filled_rectangle: decode_sprite(include_bytes!(
+                "../assets/icon-pen-filled-rectangle.png"
+            ))
+            .or_fail()?,
             pen_circle: decode_sprite(include_bytes!("../assets/icon-pen-circle.png")).or_fail()?,
             bucket: decode_sprite(include_bytes!("../assets/icon-bucket.png")).or_fail()?,
             scissor_rectangle: decode_sprite(include_bytes!(
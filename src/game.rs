@@ -3,8 +3,10 @@ use crate::tags::RENDERING_TAG;
 use crate::{
     app::App,
     event::Event,
-    io::Input,
+    io::{CanceledInput, Input, TextInput},
     model::Models,
+    pixel::PixelSize,
+    sprite_sheet::SpriteSheet,
     window::{Window, main::MainWindow},
 };
 use orfail::OrFail;
@@ -164,6 +166,16 @@ impl<S: System> Game<S> for PixcilGame {
                 let data = app.models().to_png().or_fail()?;
                 Ok(data)
             }
+            "workspaceGif" => {
+                let app = self.app.as_ref().or_fail()?;
+                let data = app.models().to_gif().or_fail()?;
+                Ok(data)
+            }
+            "spriteSheetJson" => {
+                let app = self.app.as_ref().or_fail()?;
+                let sprite_sheet = SpriteSheet::new(app.models());
+                Ok(serde_json::to_vec(&sprite_sheet).or_fail()?)
+            }
             "stateVersion" => {
                 let app = self.app.as_ref().or_fail()?;
                 let version = app.models().pixel_canvas.state_version();
@@ -177,6 +189,17 @@ impl<S: System> Game<S> for PixcilGame {
         match name {
             "notifyInputNumber" | "notifyInputSize" => {
                 let data: Input = serde_json::from_slice(data).or_fail()?;
+                let app = self.app.as_mut().or_fail()?;
+                if let Some(png_data) = app.take_sprite_sheet_import(data.id) {
+                    let cell_size = data.number.parse::<PixelSize>().or_fail()?;
+                    *app.models_mut() =
+                        Models::from_sprite_sheet(&png_data, cell_size).or_fail()?;
+                    app.request_redraw(app.screen_size().to_region());
+                    self.handle_pixcil_event(system, Some(Event::Noop))
+                        .or_fail()?;
+                    return Ok(());
+                }
+
                 let event = Event::Input {
                     id: data.id,
                     text: data.number,
@@ -184,6 +207,21 @@ impl<S: System> Game<S> for PixcilGame {
                 self.handle_pixcil_event(system, Some(event)).or_fail()?;
                 Ok(())
             }
+            "notifyInputText" => {
+                let data: TextInput = serde_json::from_slice(data).or_fail()?;
+                let event = Event::Input {
+                    id: data.id,
+                    text: data.text,
+                };
+                self.handle_pixcil_event(system, Some(event)).or_fail()?;
+                Ok(())
+            }
+            "notifyInputCancel" => {
+                let data: CanceledInput = serde_json::from_slice(data).or_fail()?;
+                let app = self.app.as_mut().or_fail()?;
+                app.take_sprite_sheet_import(data.id);
+                Ok(())
+            }
             "loadWorkspace" => {
                 let app = self.app.as_mut().or_fail()?;
                 *app.models_mut() = Models::from_png(data).or_fail()?;
@@ -193,6 +231,14 @@ impl<S: System> Game<S> for PixcilGame {
 
                 Ok(())
             }
+            "importSpriteSheet" => {
+                // The sheet is loaded after the cell size is input.
+                let app = self.app.as_mut().or_fail()?;
+                app.start_sprite_sheet_import(data.to_vec());
+                self.handle_pixcil_event(system, Some(Event::Noop))
+                    .or_fail()?;
+                Ok(())
+            }
             "disableSaveWorkspaceButton" => {
                 let app = self.app.as_mut().or_fail()?;
                 app.runtime_options.disable_save_workspace_button = true;
@@ -0,0 +1,101 @@
+use color_quant::NeuQuant;
+use orfail::{OrFail, Result};
+use std::{collections::HashMap, time::Duration};
+
+const TRANSPARENT_INDEX: u8 = 0;
+const MAX_OPAQUE_COLORS: usize = 255;
+
+// Pixels that are less opaque than this threshold become transparent.
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// Encodes RGBA32 frames (and their delays) into an animated GIF image.
+///
+/// All the frames share a global palette that has at most 255 opaque colors plus a transparent one.
+/// If the frames have more colors than that, they are quantized.
+pub fn encode_gif(
+    frames: &[Vec<u8>],
+    delays: &[Duration],
+    width: u16,
+    height: u16,
+) -> Result<Vec<u8>> {
+    (frames.len() == delays.len()).or_fail()?;
+    let palette = Palette::new(frames);
+
+    let mut encoder =
+        gif::Encoder::new(Vec::new(), width, height, &palette.rgb_bytes()).or_fail()?;
+    if frames.len() > 1 {
+        encoder.set_repeat(gif::Repeat::Infinite).or_fail()?;
+    }
+    for (frame, delay) in frames.iter().zip(delays) {
+        let indices = frame
+            .chunks_exact(4)
+            .map(|rgba| palette.index_of(rgba))
+            .collect::<Vec<_>>();
+        let mut frame =
+            gif::Frame::from_indexed_pixels(width, height, indices, Some(TRANSPARENT_INDEX));
+        frame.delay = (delay.as_millis() / 10).clamp(1, u128::from(u16::MAX)) as u16;
+        frame.dispose = gif::DisposalMethod::Background;
+        encoder.write_frame(&frame).or_fail()?;
+    }
+    encoder.into_inner().or_fail()
+}
+
+enum Palette {
+    Exact(HashMap<[u8; 3], u8>),
+    Quantized(NeuQuant),
+}
+
+impl Palette {
+    fn new(frames: &[Vec<u8>]) -> Self {
+        let opaque_pixels = || {
+            frames
+                .iter()
+                .flat_map(|frame| frame.chunks_exact(4))
+                .filter(|rgba| rgba[3] >= ALPHA_THRESHOLD)
+        };
+
+        let mut colors = HashMap::new();
+        for rgba in opaque_pixels() {
+            let next_index = colors.len() + 1;
+            colors
+                .entry([rgba[0], rgba[1], rgba[2]])
+                .or_insert(next_index);
+            if colors.len() > MAX_OPAQUE_COLORS {
+                let bytes = opaque_pixels()
+                    .flat_map(|rgba| [rgba[0], rgba[1], rgba[2], u8::MAX])
+                    .collect::<Vec<_>>();
+                return Self::Quantized(NeuQuant::new(10, MAX_OPAQUE_COLORS, &bytes));
+            }
+        }
+        Self::Exact(colors.into_iter().map(|(rgb, i)| (rgb, i as u8)).collect())
+    }
+
+    fn index_of(&self, rgba: &[u8]) -> u8 {
+        if rgba[3] < ALPHA_THRESHOLD {
+            return TRANSPARENT_INDEX;
+        }
+        match self {
+            Self::Exact(colors) => colors[&[rgba[0], rgba[1], rgba[2]]],
+            Self::Quantized(quantizer) => {
+                quantizer.index_of(&[rgba[0], rgba[1], rgba[2], u8::MAX]) as u8 + 1
+            }
+        }
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; 3];
+        match self {
+            Self::Exact(colors) => {
+                bytes.resize((colors.len() + 1) * 3, 0);
+                for (rgb, &i) in colors {
+                    let i = usize::from(i) * 3;
+                    bytes[i..i + 3].copy_from_slice(rgb);
+                }
+            }
+            Self::Quantized(quantizer) => {
+                bytes.extend(quantizer.color_map_rgb());
+            }
+        }
+        bytes
+    }
+}
@@ -6,8 +6,23 @@ pub enum IoRequest {
     SaveWorkspace,
     LoadWorkspace,
     ImportImage,
-    InputNumber { id: InputId },
-    InputSize { id: InputId },
+    ExportGif,
+    ImportSpriteSheet,
+    InputNumber {
+        id: InputId,
+    },
+    InputSize {
+        id: InputId,
+    },
+    InputText {
+        id: InputId,
+
+        /// Initial text of the input box.
+        text: String,
+
+        /// Reason why the previous input was rejected.
+        error: Option<String>,
+    },
     Vibrate,
 }
 
@@ -16,3 +31,14 @@ pub struct Input {
     pub id: InputId,
     pub number: String,
 }
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct TextInput {
+    pub id: InputId,
+    pub text: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct CanceledInput {
+    pub id: InputId,
+}
@@ -5,6 +5,7 @@ pub mod color;
 pub mod event;
 pub mod game;
 pub mod gesture;
+pub mod gif;
 pub mod io;
 pub mod marker;
 pub mod model;
@@ -12,6 +13,7 @@ pub mod pixel;
 pub mod png;
 pub mod region_ext;
 pub mod serialize;
+pub mod sprite_sheet;
 pub mod tags;
 pub mod widget;
 pub mod window;
@@ -1,19 +1,24 @@
 use self::{
-    fill::FillMarker, lasso::LassoMarker, noop::NoopMarker, pick::PickMarker, stroke::StrokeMarker,
+    ellipse::EllipseMarker, fill::FillMarker, lasso::LassoMarker, line::LineMarker,
+    noop::NoopMarker, pick::PickMarker, rectangle::RectangleMarker, stroke::StrokeMarker,
 };
 use crate::{
     app::App,
     event::{Event, MouseAction},
     io::IoRequest,
+    model::tool::ToolKind,
     pixel::{PixelPosition, PixelRegion},
 };
 use pagurus::Result;
 use std::collections::HashSet;
 
+pub mod ellipse;
 pub mod fill;
 pub mod lasso;
+pub mod line;
 pub mod noop;
 pub mod pick;
+pub mod rectangle;
 pub mod stroke;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
@@ -30,6 +35,20 @@ pub trait Mark: Default {
     fn marked_pixels(&self, app: &App) -> Box<dyn '_ + Iterator<Item = PixelPosition>>;
 }
 
+/// Returns the given position and its mirrored positions if the symmetry setting applies to the current tool.
+pub fn symmetric_positions(app: &App, position: PixelPosition) -> Vec<PixelPosition> {
+    let config = &app.models().config;
+    let tool = app.models().tool.tool_kind();
+    if !config.symmetry.is_enabled()
+        || !matches!(tool, ToolKind::Draw | ToolKind::Erase | ToolKind::Fill)
+    {
+        return vec![position];
+    }
+    config
+        .symmetry
+        .positions(config.frame.get_base_region(), position)
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MarkerKind {
     #[default]
@@ -38,6 +57,10 @@ pub enum MarkerKind {
     Lasso,
     Pick,
     Fill,
+    Line,
+    Rectangle,
+    FilledRectangle,
+    Ellipse,
 }
 
 #[derive(Debug)]
@@ -47,6 +70,10 @@ pub enum Marker {
     Lasso(LassoMarker),
     Pick(PickMarker),
     Fill(FillMarker),
+    Line(LineMarker),
+    Rectangle(RectangleMarker),
+    FilledRectangle(RectangleMarker),
+    Ellipse(EllipseMarker),
 }
 
 impl Marker {
@@ -57,6 +84,10 @@ impl Marker {
             MarkerKind::Lasso => Self::Lasso(Default::default()),
             MarkerKind::Pick => Self::Pick(Default::default()),
             MarkerKind::Fill => Self::Fill(Default::default()),
+            MarkerKind::Line => Self::Line(Default::default()),
+            MarkerKind::Rectangle => Self::Rectangle(Default::default()),
+            MarkerKind::FilledRectangle => Self::FilledRectangle(RectangleMarker::filled()),
+            MarkerKind::Ellipse => Self::Ellipse(Default::default()),
         }
     }
 }
@@ -75,17 +106,34 @@ impl Mark for Marker {
             Marker::Lasso(x) => x.mark(app, position, mouse),
             Marker::Pick(x) => x.mark(app, position, mouse),
             Marker::Fill(x) => x.mark(app, position, mouse),
+            Marker::Line(x) => x.mark(app, position, mouse),
+            Marker::Rectangle(x) => x.mark(app, position, mouse),
+            Marker::FilledRectangle(x) => x.mark(app, position, mouse),
+            Marker::Ellipse(x) => x.mark(app, position, mouse),
         }
     }
 
     fn marked_pixels(&self, app: &App) -> Box<dyn '_ + Iterator<Item = PixelPosition>> {
-        match self {
+        let pixels = match self {
             Marker::Stroke(x) => x.marked_pixels(app),
-            Marker::Noop(x) => x.marked_pixels(app),
+            Marker::Noop(x) => return x.marked_pixels(app),
             Marker::Lasso(x) => x.marked_pixels(app),
-            Marker::Pick(x) => x.marked_pixels(app),
-            Marker::Fill(x) => x.marked_pixels(app),
+            Marker::Pick(x) => return x.marked_pixels(app),
+            Marker::Fill(x) => return x.marked_pixels(app),
+            Marker::Line(x) => x.marked_pixels(app),
+            Marker::Rectangle(x) => x.marked_pixels(app),
+            Marker::FilledRectangle(x) => x.marked_pixels(app),
+            Marker::Ellipse(x) => x.marked_pixels(app),
+        };
+        if !app.models().config.symmetry.is_enabled() {
+            return pixels;
         }
+
+        // Mirrored positions can overlap with the original ones, so they are deduplicated here.
+        let pixels = pixels
+            .flat_map(|p| symmetric_positions(app, p))
+            .collect::<HashSet<_>>();
+        Box::new(pixels.into_iter())
     }
 }
 
@@ -105,6 +153,10 @@ impl MarkerHandler {
             Marker::Lasso(_) => MarkerKind::Lasso,
             Marker::Pick(_) => MarkerKind::Pick,
             Marker::Fill(_) => MarkerKind::Fill,
+            Marker::Line(_) => MarkerKind::Line,
+            Marker::Rectangle(_) => MarkerKind::Rectangle,
+            Marker::FilledRectangle(_) => MarkerKind::FilledRectangle,
+            Marker::Ellipse(_) => MarkerKind::Ellipse,
         }
     }
 
@@ -116,6 +168,17 @@ impl MarkerHandler {
         self.marker.marked_pixels(app)
     }
 
+    /// Returns the start and end positions of the gradient being dragged by the FILL tool.
+    ///
+    /// If the given position is in a mirrored area, the mirrored line is returned.
+    pub fn gradient_line(&self, position: PixelPosition) -> Option<(PixelPosition, PixelPosition)> {
+        if let Marker::Fill(x) = &self.marker {
+            x.gradient_line(position)
+        } else {
+            None
+        }
+    }
+
     pub fn is_completed(&self) -> bool {
         self.mouse == MouseState::Clicked
     }
@@ -181,7 +244,8 @@ impl MarkerHandler {
 
         self.marker.mark(app, pixel_position, self.mouse);
         let marked = self.marker.marked_pixels(app).collect::<HashSet<_>>();
-        if old_mouse != self.mouse {
+        if old_mouse != self.mouse || self.gradient_line(pixel_position).is_some() {
+            // The colors of a gradient change while the mouse is being dragged.
             self.request_redraw(app, marked.union(&self.last_marked).copied());
         } else {
             self.request_redraw(app, marked.symmetric_difference(&self.last_marked).copied());
@@ -0,0 +1,78 @@
+use super::{Mark, MouseState};
+use crate::{
+    app::App,
+    pixel::{PixelPosition, PixelRegion},
+};
+use std::collections::HashSet;
+
+#[derive(Debug, Default)]
+pub struct EllipseMarker {
+    start: Option<PixelPosition>,
+    marked: HashSet<PixelPosition>,
+}
+
+impl EllipseMarker {
+    fn ellipse(start: PixelPosition, end: PixelPosition) -> HashSet<PixelPosition> {
+        let region = PixelRegion::from_positions([start, end].into_iter());
+        let size = region.size();
+        let rx = size.width as f64 / 2.0;
+        let ry = size.height as f64 / 2.0;
+        let cx = region.start.x as f64 + rx;
+        let cy = region.start.y as f64 + ry;
+        let is_inside = |p: PixelPosition| {
+            let dx = (p.x as f64 + 0.5 - cx) / rx;
+            let dy = (p.y as f64 + 0.5 - cy) / ry;
+            region.contains(p) && dx * dx + dy * dy <= 1.0
+        };
+
+        // Keep only the inner pixels that touch the outside of the ellipse.
+        region
+            .pixels()
+            .filter(|&p| is_inside(p))
+            .filter(|&p| {
+                [p.move_x(-1), p.move_x(1), p.move_y(-1), p.move_y(1)]
+                    .into_iter()
+                    .any(|q| !is_inside(q))
+            })
+            .collect()
+    }
+}
+
+impl Mark for EllipseMarker {
+    fn mark(&mut self, app: &App, position: PixelPosition, mouse: MouseState) {
+        let unit = app.models().config.minimum_pixel_size;
+        let position = unit.normalize(position);
+        match mouse {
+            MouseState::Neutral => {
+                self.start = None;
+                self.marked = [position].into_iter().collect();
+            }
+            MouseState::Pressing => {
+                if let Some(start) = self.start {
+                    self.marked = Self::ellipse(start, position);
+                } else {
+                    self.start = Some(position);
+                    self.marked = [position].into_iter().collect()
+                }
+            }
+            MouseState::Clicked | MouseState::ClickConsumed => {
+                if let Some(start) = self.start {
+                    self.marked = Self::ellipse(start, position);
+                    self.start = None;
+                } else {
+                    self.marked = [position].into_iter().collect()
+                }
+            }
+        }
+    }
+
+    fn marked_pixels(&self, app: &App) -> Box<dyn '_ + Iterator<Item = PixelPosition>> {
+        let unit = app.models().config.minimum_pixel_size;
+        Box::new(
+            self.marked
+                .iter()
+                .copied()
+                .flat_map(move |p| unit.denormalize_to_region(p).pixels()),
+        )
+    }
+}
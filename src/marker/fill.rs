@@ -1,55 +1,243 @@
-use super::{Mark, MouseState};
-use crate::{app::App, pixel::PixelPosition};
-use std::collections::HashSet;
+use super::{Mark, MouseState, symmetric_positions};
+use crate::{
+    app::App,
+    model::{config::FillMode, tool::ToolKind},
+    pixel::PixelPosition,
+};
+use pagurus::image::Rgba;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Default)]
 pub struct FillMarker {
     marked: HashSet<PixelPosition>,
     cannot_fill: HashSet<PixelPosition>,
+
+    // Start and end positions of the dragged gradient line (only used in the gradient mode).
+    gradient: Option<(PixelPosition, PixelPosition)>,
+
+    // Mirrored start positions of the gradient line for the pixels in the mirrored areas.
+    mirrored_gradient_starts: HashMap<PixelPosition, PixelPosition>,
 }
 
 impl FillMarker {
-    fn fill_same_color_area(&mut self, app: &App, position: PixelPosition) {
+    pub fn gradient_line(&self, position: PixelPosition) -> Option<(PixelPosition, PixelPosition)> {
+        let (start, end) = self.gradient?;
+        let Some(&mirrored_start) = self.mirrored_gradient_starts.get(&position) else {
+            return Some((start, end));
+        };
+
+        // The end position is mirrored across the same axes as the start position.
+        let mut mirrored_end = end;
+        if mirrored_start.x != start.x {
+            mirrored_end.x = mirrored_start.x + start.x - end.x;
+        }
+        if mirrored_start.y != start.y {
+            mirrored_end.y = mirrored_start.y + start.y - end.y;
+        }
+        Some((mirrored_start, mirrored_end))
+    }
+
+    fn mark_gradient(&mut self, app: &App, position: PixelPosition) {
+        if let Some((_, end)) = &mut self.gradient {
+            *end = position;
+            return;
+        }
+
+        // The areas are determined by the position where the mouse is pressed (and its mirrored positions).
         self.marked.clear();
+        self.mirrored_gradient_starts.clear();
+        // The pressed position goes first so that an area connected to its mirror is not regarded as mirrored.
+        let starts = std::iter::once(position).chain(symmetric_positions(app, position));
+        for start in starts {
+            if self.marked.contains(&start) || self.cannot_fill.contains(&start) {
+                continue;
+            }
+            let marked = std::mem::take(&mut self.marked);
+            self.fill_same_color_area(app, start);
+            let area = std::mem::replace(&mut self.marked, marked);
+            if start != position {
+                self.mirrored_gradient_starts
+                    .extend(area.iter().map(|&p| (p, start)));
+            }
+            self.marked.extend(area);
+        }
+        if !self.marked.is_empty() {
+            self.gradient = Some((position, position));
+        }
+    }
+
+    fn fill_same_color_area(&mut self, app: &App, position: PixelPosition) {
+        let config = &app.models().config;
+        let tolerance = config.fill_tolerance;
+        let bounds = config
+            .fill_within_frame
+            .then(|| config.frame.get_cell_region(position));
+
+        let mut marked = HashSet::new();
         let pixel_canvas = &app.models().pixel_canvas;
         let region = pixel_canvas.region();
         let color = pixel_canvas.get_direct_pixel(position);
+        let fills_transparent = is_similar_color(color, None, tolerance);
         let mut stack = vec![position];
         let mut visited = HashSet::new();
         while let Some(position) = stack.pop() {
-            if color.is_none() && !region.contains(position) {
-                self.cannot_fill.extend(self.marked.drain());
-                break;
+            if let Some(bounds) = bounds {
+                if !bounds.contains(position) {
+                    continue;
+                }
+            } else if fills_transparent && !region.contains(position) {
+                self.cannot_fill.extend(marked);
+                return;
             }
             if !visited.insert(position) {
                 // Already visited.
                 continue;
             }
-            if pixel_canvas.get_direct_pixel(position) != color {
+            if !is_similar_color(pixel_canvas.get_direct_pixel(position), color, tolerance) {
                 continue;
             }
 
-            self.marked.insert(position);
+            marked.insert(position);
             stack.extend([
                 position.move_x(-1),
                 position.move_x(1),
                 position.move_y(-1),
                 position.move_y(1),
             ]);
+            if config.fill_diagonal {
+                stack.extend([
+                    position.move_x(-1).move_y(-1),
+                    position.move_x(1).move_y(-1),
+                    position.move_x(-1).move_y(1),
+                    position.move_x(1).move_y(1),
+                ]);
+            }
+        }
+        self.marked.extend(marked);
+    }
+
+    fn fill_same_color_pixels(&mut self, app: &App, position: PixelPosition, mode: FillMode) {
+        let config = &app.models().config;
+        let tolerance = config.fill_tolerance;
+        let pixel_canvas = &app.models().pixel_canvas;
+        let region = if mode == FillMode::Frame {
+            config.frame.get_cell_region(position)
+        } else {
+            config.frame.get_full_region(config)
+        };
+
+        let color = pixel_canvas.get_direct_pixel(position);
+        if color.is_none() && !region.contains(position) {
+            self.cannot_fill.insert(position);
+            return;
+        }
+
+        for other in pixel_canvas.colors() {
+            if !is_similar_color(Some(other), color, tolerance) {
+                continue;
+            }
+            let positions = pixel_canvas.color_positions(other);
+            if mode == FillMode::Canvas {
+                self.marked.extend(positions);
+            } else {
+                self.marked
+                    .extend(positions.filter(|p| region.contains(*p)));
+            }
+        }
+
+        if is_similar_color(None, color, tolerance) {
+            // Transparent pixels are bounded by the frame (or the frames).
+            let positions = region
+                .pixels()
+                .filter(|p| pixel_canvas.get_direct_pixel(*p).is_none());
+            self.marked.extend(positions);
         }
     }
 }
 
 impl Mark for FillMarker {
-    fn mark(&mut self, app: &App, position: PixelPosition, _mouse: MouseState) {
+    fn mark(&mut self, app: &App, position: PixelPosition, mouse: MouseState) {
+        // The SELECT tool also uses this marker, but the fill mode only applies to the FILL tool.
+        let mode = if app.models().tool.tool_kind() == ToolKind::Fill {
+            app.models().config.fill_mode
+        } else {
+            FillMode::Area
+        };
+        if mode == FillMode::Gradient && mouse != MouseState::Neutral {
+            self.mark_gradient(app, position);
+            return;
+        }
+        self.gradient = None;
+        self.mirrored_gradient_starts.clear();
+
         if self.marked.contains(&position) || self.cannot_fill.contains(&position) {
             return;
         }
 
-        self.fill_same_color_area(app, position);
+        self.marked.clear();
+        for position in symmetric_positions(app, position) {
+            if self.marked.contains(&position) || self.cannot_fill.contains(&position) {
+                continue;
+            }
+            match mode {
+                FillMode::Area | FillMode::Gradient => self.fill_same_color_area(app, position),
+                FillMode::Frame | FillMode::Canvas => {
+                    self.fill_same_color_pixels(app, position, mode)
+                }
+            }
+        }
     }
 
     fn marked_pixels(&self, _app: &App) -> Box<dyn '_ + Iterator<Item = PixelPosition>> {
         Box::new(self.marked.iter().copied())
     }
 }
+
+/// Returns whether the RGBA distance of the two colors is within the tolerance
+/// (`None` is regarded as the fully transparent black).
+///
+/// The distance is the root mean square of the channel differences (from 0 to 255).
+fn is_similar_color(a: Option<Rgba>, b: Option<Rgba>, tolerance: u8) -> bool {
+    if tolerance == 0 {
+        return a == b;
+    }
+
+    let a = a.unwrap_or(Rgba::new(0, 0, 0, 0));
+    let b = b.unwrap_or(Rgba::new(0, 0, 0, 0));
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2);
+    let squared = d(a.r, b.r) + d(a.g, b.g) + d(a.b, b.b) + d(a.a, b.a);
+    squared <= i32::from(tolerance).pow(2) * 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_similar_color_works() {
+        let black = Some(Rgba::new(0, 0, 0, 255));
+        let gray = Some(Rgba::new(10, 10, 10, 255));
+        assert!(!is_similar_color(black, gray, 0));
+        assert!(!is_similar_color(black, gray, 8));
+        assert!(is_similar_color(black, gray, 9));
+
+        assert!(is_similar_color(None, None, 0));
+        assert!(!is_similar_color(None, Some(Rgba::new(0, 0, 0, 20)), 9));
+        assert!(is_similar_color(None, Some(Rgba::new(0, 0, 0, 20)), 10));
+    }
+
+    #[test]
+    fn mirrored_gradient_line_works() {
+        let p = PixelPosition::from_xy;
+        let mut marker = FillMarker {
+            gradient: Some((p(1, 2), p(3, 5))),
+            ..Default::default()
+        };
+        marker.mirrored_gradient_starts.insert(p(8, 2), p(8, 2));
+        marker.mirrored_gradient_starts.insert(p(8, 7), p(8, 7));
+
+        assert_eq!(marker.gradient_line(p(0, 0)), Some((p(1, 2), p(3, 5))));
+        assert_eq!(marker.gradient_line(p(8, 2)), Some((p(8, 2), p(6, 5))));
+        assert_eq!(marker.gradient_line(p(8, 7)), Some((p(8, 7), p(6, 4))));
+    }
+}
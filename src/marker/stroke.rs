@@ -9,13 +9,35 @@ use std::collections::HashSet;
 pub struct StrokeMarker {
     last: Option<PixelPosition>,
     marked: HashSet<PixelPosition>,
+
+    // Positions in drawing order (only used in the pixel-perfect mode).
+    path: Vec<PixelPosition>,
+}
+
+impl StrokeMarker {
+    fn mark_pixel_perfect(&mut self, position: PixelPosition) {
+        if let Some(last) = self.last {
+            let mut line = PixelLine::new(last, position).pixels().collect::<Vec<_>>();
+            if line.first() != Some(&last) {
+                line.reverse();
+            }
+            for position in line.into_iter().skip(1) {
+                push_pixel_perfect(&mut self.path, position);
+            }
+        } else {
+            self.path = vec![position];
+        }
+        self.marked = self.path.iter().copied().collect();
+    }
 }
 
 impl Mark for StrokeMarker {
     fn mark(&mut self, app: &App, position: PixelPosition, mouse: MouseState) {
         let unit = app.models().config.minimum_pixel_size;
         let position = unit.normalize(position);
-        if let Some(last) = self.last {
+        if app.models().config.pixel_perfect {
+            self.mark_pixel_perfect(position);
+        } else if let Some(last) = self.last {
             self.marked.extend(PixelLine::new(last, position).pixels());
         } else {
             self.marked = [position].into_iter().collect()
@@ -37,3 +59,50 @@ impl Mark for StrokeMarker {
         )
     }
 }
+
+/// Appends a position to a stroke path, removing the corner pixel of an L-shape that the position makes.
+fn push_pixel_perfect(path: &mut Vec<PixelPosition>, position: PixelPosition) {
+    if path.last() == Some(&position) {
+        return;
+    }
+    path.push(position);
+
+    let [.., a, b, c] = path[..] else {
+        return;
+    };
+    let is_adjacent =
+        |p: PixelPosition, q: PixelPosition| (p.x - q.x).abs() + (p.y - q.y).abs() == 1;
+    let is_diagonal = (a.x - c.x).abs() == 1 && (a.y - c.y).abs() == 1;
+    if is_adjacent(a, b) && is_adjacent(b, c) && is_diagonal {
+        path.remove(path.len() - 2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_pixel_perfect_works() {
+        let mut path = Vec::new();
+        for (x, y) in [(0, 0), (1, 0), (1, 1), (2, 1), (3, 1), (3, 2)] {
+            push_pixel_perfect(&mut path, PixelPosition::from_xy(x, y));
+        }
+        assert_eq!(
+            path,
+            [
+                PixelPosition::from_xy(0, 0),
+                PixelPosition::from_xy(1, 1),
+                PixelPosition::from_xy(2, 1),
+                PixelPosition::from_xy(3, 2),
+            ]
+        );
+
+        // Straight lines are kept as they are.
+        let mut path = Vec::new();
+        for x in 0..3 {
+            push_pixel_perfect(&mut path, PixelPosition::from_xy(x, 0));
+        }
+        assert_eq!(path.len(), 3);
+    }
+}
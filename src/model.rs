@@ -1,15 +1,22 @@
-use self::{config::ConfigModel, pixel_canvas::PixelCanvasModel, tool::ToolModel};
+use self::{
+    config::{Animation, ConfigModel, Layer},
+    pixel_canvas::PixelCanvasModel,
+    tool::ToolModel,
+};
+use crate::gif::encode_gif;
 use crate::pixel::PixelSize;
-use crate::png::decode_sprite;
+use crate::png::{decode_animation, decode_sprite, is_indexable, to_indexed_frames};
 use crate::{
     pixel::{Pixel, PixelPosition},
     serialize::{Deserialize, Serialize},
 };
 use orfail::{OrFail, Result};
 use pagurus::image::Rgba;
+use pagurus::spatial::Size;
 use png::chunk::ChunkType;
 use std::collections::HashSet;
 use std::io::{Read, Write};
+use std::time::Duration;
 
 pub mod attributes;
 pub mod config;
@@ -20,6 +27,9 @@ pub const PNG_CHUNK_TYPE: ChunkType = ChunkType(*b"siLE");
 pub const MAGIC_NUMBER: [u8; 6] = *b"PIXCIL";
 pub const FORMAT_VERSION: u16 = 0;
 
+// The maximum width and height of PNG images.
+const PNG_MAX_SIDE: u32 = i32::MAX as u32;
+
 #[derive(Debug, Default)]
 pub struct Models {
     pub config: ConfigModel,
@@ -58,42 +68,32 @@ impl Models {
     }
 
     pub fn to_png(&self) -> Result<Vec<u8>> {
-        let bg_color = self
-            .config
-            .background_color
-            .unwrap_or(Rgba::new(0, 0, 0, 0));
         let mut frame_count = self.config.animation.enabled_frame_count();
-        let mut frames = (0..frame_count)
-            .map(|frame| {
-                self.config
-                    .frame
-                    .get_preview_region(&self.config, frame as usize)
-                    .pixels()
-                    .flat_map(|position| {
-                        let color =
-                            if let Some(c) = self.pixel_canvas.get_pixel(&self.config, position) {
-                                c.alpha_blend(bg_color)
-                            } else {
-                                bg_color
-                            };
-                        [color.r, color.g, color.b, color.a].into_iter()
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        let mut image_size = self.config.frame.get_base_region().size();
+        let mut frames = self.render_frames();
+        let mut image_size = self.export_frame_size();
         if !self.config.apng() {
+            let frame_width = image_size.width as usize;
+            image_size.width = image_size
+                .width
+                .checked_mul(u32::from(frame_count))
+                .filter(|&width| width <= PNG_MAX_SIDE)
+                .or_fail_with(|()| {
+                    format!(
+                        "too wide sprite sheet: {} frames of {} pixels",
+                        frame_count, frame_width
+                    )
+                })?;
+
             let mut sprite_sheet_frame = Vec::new();
             for row in 0..image_size.height {
                 for frame in &frames {
-                    let row_start = row as usize * image_size.width as usize * 4;
-                    let row_end = row_start + image_size.width as usize * 4;
+                    let row_start = row as usize * frame_width * 4;
+                    let row_end = row_start + frame_width * 4;
                     sprite_sheet_frame.extend_from_slice(&frame[row_start..row_end]);
                 }
             }
 
             frames = vec![sprite_sheet_frame];
-            image_size.width *= frame_count;
             frame_count = 1;
         }
 
@@ -102,24 +102,36 @@ impl Models {
 
         let mut png_data = Vec::new();
         {
-            let mut encoder = png::Encoder::new(
-                &mut png_data,
-                u32::from(image_size.width),
-                u32::from(image_size.height),
-            );
-            encoder.set_color(png::ColorType::Rgba);
-            encoder.set_depth(png::BitDepth::Eight);
+            let mut encoder = png::Encoder::new(&mut png_data, image_size.width, image_size.height);
             encoder.set_compression(png::Compression::Best);
+            if let Some(indexed) = to_indexed_frames(&frames, image_size.width as usize) {
+                encoder.set_color(png::ColorType::Indexed);
+                encoder.set_depth(indexed.bit_depth);
+                encoder.set_palette(indexed.palette);
+                if !indexed.trns.is_empty() {
+                    encoder.set_trns(indexed.trns);
+                }
+                frames = indexed.frames;
+            } else {
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+            }
 
             if frame_count > 1 {
                 encoder.set_animated(frame_count as u32, 0).or_fail()?;
-                encoder
-                    .set_frame_delay(1, self.config.animation.fps() as u16)
-                    .or_fail()?;
             }
 
             let mut writer = encoder.write_header().or_fail()?;
-            for image_data in &frames {
+            for (frame, image_data) in frames.iter().enumerate() {
+                if frame_count > 1 {
+                    if let Some(millis) = self.config.frame_durations.get_millis(frame) {
+                        writer.set_frame_delay(millis, 1000).or_fail()?;
+                    } else {
+                        writer
+                            .set_frame_delay(1, self.config.animation.fps() as u16)
+                            .or_fail()?;
+                    }
+                }
                 writer.write_image_data(image_data).or_fail()?;
             }
             writer.write_chunk(PNG_CHUNK_TYPE, &metadata).or_fail()?;
@@ -127,44 +139,217 @@ impl Models {
         Ok(png_data)
     }
 
-    pub fn from_png(png_data: &[u8]) -> Result<Self> {
-        let mut decoder = png::StreamingDecoder::new();
-        decoder.set_ignore_text_chunk(true);
-        let mut offset = 0;
-        while offset < png_data.len() {
-            let mut buf = Vec::new();
-            let (read_size, decoded) = decoder.update(&png_data[offset..], &mut buf).or_fail()?;
-            offset += read_size;
-
-            if let png::Decoded::ChunkBegin(_, PNG_CHUNK_TYPE) = decoded {
-                let mut reader = &png_data[offset..];
-                let models = Self::deserialize(&mut reader).or_fail()?;
-                return Ok(models);
+    /// Returns whether [`Models::to_png()`] writes the image in the indexed color format.
+    pub fn is_indexed_png(&self) -> bool {
+        is_indexable(&self.render_frames())
+    }
+
+    pub fn to_gif(&self) -> Result<Vec<u8>> {
+        let size = self.export_frame_size();
+        let width = u16::try_from(size.width)
+            .or_fail_with(|e| format!("too wide GIF frame: {} pixels ({e})", size.width))?;
+        let height = u16::try_from(size.height)
+            .or_fail_with(|e| format!("too tall GIF frame: {} pixels ({e})", size.height))?;
+        let frames = self.render_frames();
+        let delays = (0..frames.len())
+            .map(|frame| self.config.frame_duration(frame))
+            .collect::<Vec<_>>();
+        encode_gif(&frames, &delays, width, height).or_fail()
+    }
+
+    // Returns the size of an exported frame (the scaled size can exceed `u16`).
+    fn export_frame_size(&self) -> Size {
+        let size = self.config.frame.get_base_region().size();
+        let scale = u32::from(self.config.export_scale.get());
+        Size::from_wh(
+            u32::from(size.width) * scale,
+            u32::from(size.height) * scale,
+        )
+    }
+
+    // Returns the RGBA32 bytes of each animation frame (scaled by `ExportScale`).
+    fn render_frames(&self) -> Vec<Vec<u8>> {
+        let bg_color = self
+            .config
+            .background_color
+            .unwrap_or(Rgba::new(0, 0, 0, 0));
+        let frame_count = self.config.animation.enabled_frame_count();
+        (0..frame_count)
+            .map(|frame| {
+                self.config
+                    .frame
+                    .get_preview_region(&self.config, frame as usize)
+                    .pixels()
+                    .flat_map(|position| {
+                        let color =
+                            if let Some(c) = self.pixel_canvas.get_pixel(&self.config, position) {
+                                c.alpha_blend(bg_color)
+                            } else {
+                                bg_color
+                            };
+                        [color.r, color.g, color.b, color.a].into_iter()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .map(|frame| self.scale_frame(frame))
+            .collect::<Vec<_>>()
+    }
+
+    // Upscales a frame using the nearest-neighbour method.
+    fn scale_frame(&self, frame: Vec<u8>) -> Vec<u8> {
+        let scale = usize::from(self.config.export_scale.get());
+        if scale == 1 {
+            return frame;
+        }
+
+        let row_size = self.config.frame.get_base_region().size().width as usize * 4;
+        let mut scaled = Vec::with_capacity(frame.len() * scale * scale);
+        for row in frame.chunks_exact(row_size) {
+            let scaled_row = row
+                .chunks_exact(4)
+                .flat_map(|rgba| std::iter::repeat_n(rgba, scale).flatten())
+                .copied()
+                .collect::<Vec<_>>();
+            for _ in 0..scale {
+                scaled.extend_from_slice(&scaled_row);
             }
         }
+        scaled
+    }
+
+    pub fn from_png(png_data: &[u8]) -> Result<Self> {
+        if let Some(mut reader) = find_metadata(png_data).or_fail()? {
+            let models = Self::deserialize(&mut reader).or_fail()?;
+            return Ok(models);
+        }
 
         // Load the image with the default settings.
         //
-        // TODO: Support animated PNG
+        // If the image is an animated PNG, its frames are laid out horizontally.
+        let mut models = Self::default();
+        let frames = decode_animation(png_data).or_fail()?;
+        (frames.len() <= usize::from(Animation::MAX_FRAME_COUNT))
+            .or_fail_with(|()| format!("too many animation frames: {}", frames.len()))?;
+        let frame_size = frames.first().or_fail()?.sprite.size();
+        let frame_width = i16::try_from(frame_size.width).or_fail()?;
+        i16::try_from(frame_size.width as usize * frames.len())
+            .or_fail_with(|e| format!("too wide animation: {e}"))?;
+        models
+            .pixel_canvas
+            .draw_pixels(
+                &models.config,
+                frames.iter().enumerate().flat_map(|(i, frame)| {
+                    let offset_x = frame_width * i as i16;
+                    frame.sprite.pixels().map(move |(pos, rgba)| {
+                        let position =
+                            PixelPosition::from_xy(pos.x as i16 + offset_x, pos.y as i16);
+                        Pixel::new(position, rgba)
+                    })
+                }),
+            )
+            .or_fail()?;
+        models.pixel_canvas.forget_oldest_command();
+        models.config.frame.set_width(frame_size.width as u16);
+        models.config.frame.set_height(frame_size.height as u16);
+
+        if frames.len() > 1 {
+            let total_delay = frames.iter().map(|frame| frame.delay).sum::<Duration>();
+            let fps = if total_delay.is_zero() {
+                Animation::MAX_FPS
+            } else {
+                (frames.len() as f64 / total_delay.as_secs_f64())
+                    .round()
+                    .clamp(Animation::MIN_FPS as f64, Animation::MAX_FPS as f64)
+                    as u8
+            };
+            models.config.animation.set_enabled(true);
+            models.config.animation.set_frame_count(frames.len() as u16);
+            models.config.animation.set_fps(fps);
+            if frames.iter().any(|frame| frame.delay != frames[0].delay) {
+                for (i, frame) in frames.iter().enumerate() {
+                    let millis = frame.delay.as_millis().min(u128::from(u16::MAX)) as u16;
+                    models.config.frame_durations.set_millis(i, Some(millis));
+                }
+            }
+        }
+
+        Ok(models)
+    }
+
+    /// Loads a (non-Pixcil) sprite sheet image whose cells have the given size.
+    ///
+    /// The columns of the sheet become animation frames and the rows become layers.
+    /// If the sheet has more rows than the maximum number of layers,
+    /// all the cells are laid out horizontally as animation frames instead.
+    pub fn from_sprite_sheet(png_data: &[u8], cell_size: PixelSize) -> Result<Self> {
+        (cell_size.width > 0 && cell_size.height > 0)
+            .or_fail_with(|()| format!("invalid sprite sheet cell size: {cell_size:?}"))?;
+
         let mut models = Self::default();
         let image = decode_sprite(png_data).or_fail()?;
+        let image_size = image.size();
+        let cell_width = u32::from(cell_size.width);
+        let cell_height = u32::from(cell_size.height);
+        let columns = image_size.width.div_ceil(cell_width);
+        let rows = image_size.height.div_ceil(cell_height);
+        let (frame_count, layer_count) = if rows <= u32::from(Layer::MAX) {
+            (columns, rows)
+        } else {
+            (columns * rows, 1)
+        };
+        (frame_count <= u32::from(Animation::MAX_FRAME_COUNT))
+            .or_fail_with(|()| format!("too many sprite sheet cells: {frame_count}"))?;
+        i16::try_from(frame_count * cell_width)
+            .or_fail_with(|e| format!("too wide sprite sheet: {e}"))?;
+        i16::try_from(layer_count * cell_height)
+            .or_fail_with(|e| format!("too tall sprite sheet: {e}"))?;
+
         models
             .pixel_canvas
             .draw_pixels(
                 &models.config,
                 image.pixels().map(|(pos, rgba)| {
-                    Pixel::new(PixelPosition::from_xy(pos.x as i16, pos.y as i16), rgba)
+                    let (mut x, mut y) = (pos.x as u32, pos.y as u32);
+                    if layer_count == 1 {
+                        let row = y / cell_height;
+                        x += row * columns * cell_width;
+                        y %= cell_height;
+                    }
+                    Pixel::new(PixelPosition::from_xy(x as i16, y as i16), rgba)
                 }),
             )
             .or_fail()?;
         models.pixel_canvas.forget_oldest_command();
-        models.config.frame.set_width(image.size().width as u16);
-        models.config.frame.set_height(image.size().height as u16);
+        models.config.frame.set_from_pixel_size(cell_size);
+        if frame_count > 1 {
+            models.config.animation.set_enabled(true);
+            models.config.animation.set_frame_count(frame_count as u16);
+        }
+        if layer_count > 1 {
+            models.config.layer.set_enabled(true);
+            models.config.layer.set_count(layer_count as u16);
+        }
 
         Ok(models)
     }
 }
 
+fn find_metadata(png_data: &[u8]) -> Result<Option<&[u8]>> {
+    let mut decoder = png::StreamingDecoder::new();
+    decoder.set_ignore_text_chunk(true);
+    let mut offset = 0;
+    while offset < png_data.len() {
+        let mut buf = Vec::new();
+        let (read_size, decoded) = decoder.update(&png_data[offset..], &mut buf).or_fail()?;
+        offset += read_size;
+
+        if let png::Decoded::ChunkBegin(_, PNG_CHUNK_TYPE) = decoded {
+            return Ok(Some(&png_data[offset..]));
+        }
+    }
+    Ok(None)
+}
+
 impl Serialize for Models {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         MAGIC_NUMBER.serialize(writer).or_fail()?;
@@ -174,7 +359,14 @@ impl Serialize for Models {
         config_size.serialize(writer).or_fail()?;
         self.config.serialize(writer).or_fail()?;
 
-        self.pixel_canvas.serialize(writer).or_fail()?;
+        if self.config.persist_undo_history {
+            let max_commands = self.config.max_undos.get() as usize;
+            self.pixel_canvas
+                .serialize_with_history(writer, max_commands)
+                .or_fail()?;
+        } else {
+            self.pixel_canvas.serialize(writer).or_fail()?;
+        }
         Ok(())
     }
 }
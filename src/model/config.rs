@@ -8,6 +8,7 @@ use orfail::{OrFail, Result};
 use pagurus::image::Rgba;
 use pagurus::spatial::Position;
 use std::{
+    collections::BTreeMap,
     io::{Read, Write},
     time::Duration,
 };
@@ -17,7 +18,6 @@ pub struct ConfigModel {
     pub zoom: Zoom,
     pub camera: Camera,
     pub minimum_pixel_size: MinimumPixelSize,
-    // TODO: remove this field in the future version
     pub max_undos: MaxUndos,
     pub color: DrawingColor,
     pub frame: FrameRegion,
@@ -34,12 +34,97 @@ pub struct ConfigModel {
     pub gesture: bool,
     pub background_color: Option<Rgba>,
     pub apng: Option<bool>,
+    pub persist_undo_history: bool,
+    pub export_scale: ExportScale,
+    pub frame_durations: FrameDurations,
+    pub animation_tags: AnimationTags,
+    pub layer_attrs: LayerAttributesTable,
+    pub onion_skin: OnionSkin,
+    pub symmetry: Symmetry,
+    pub pixel_perfect: bool,
+    pub fill_mode: FillMode,
+    pub fill_tolerance: u8,
+    pub fill_diagonal: bool,
+    pub fill_within_frame: bool,
+    pub dither: Dither,
+    pub gradient: Gradient,
 }
 
 impl ConfigModel {
     pub fn apng(&self) -> bool {
         self.apng.unwrap_or(true)
     }
+
+    /// Returns the frames to be played in one loop of the preview animation.
+    ///
+    /// If an animation tag is selected, only the frames of the tag are played.
+    pub fn playing_frames(&self) -> Vec<usize> {
+        let frame_count = usize::from(self.animation.enabled_frame_count());
+        let frames = self
+            .animation_tags
+            .selected()
+            .map(|tag| tag.frames())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|&frame| frame < frame_count)
+            .collect::<Vec<_>>();
+        if frames.is_empty() {
+            (0..frame_count).collect()
+        } else {
+            frames
+        }
+    }
+
+    pub fn is_locked_position(&self, position: PixelPosition) -> bool {
+        let frame_count = self.animation.enabled_frame_count();
+        self.layer
+            .layer_index(self.frame, frame_count, position)
+            .is_some_and(|i| self.layer_attrs.get(i).locked)
+    }
+
+    pub fn band_config(&self) -> BandConfig {
+        BandConfig {
+            layer_count: self.layer.count(),
+            layer_attrs: self.layer_attrs,
+            frame_count: self.animation.frame_count(),
+            frame_durations: self.frame_durations.clone(),
+            animation_tags: self.animation_tags.clone(),
+        }
+    }
+
+    pub fn set_band_config(&mut self, band_config: &BandConfig) {
+        self.layer.set_count(band_config.layer_count);
+        self.layer_attrs = band_config.layer_attrs;
+        self.animation.set_frame_count(band_config.frame_count);
+        self.frame_durations = band_config.frame_durations.clone();
+        self.animation_tags = band_config.animation_tags.clone();
+    }
+
+    /// Updates the layer count and attributes after layers are rearranged by the given edit.
+    ///
+    /// This is called by `PixelCanvasModel::edit_layers()` along with rearranging the pixels.
+    pub fn edit_layers(&mut self, edit: BandEdit) {
+        let count = usize::from(self.layer.count());
+        self.layer.set_count(edit.applied_count(count) as u16);
+        self.layer_attrs.edit(edit);
+    }
+
+    /// Updates the frame count, durations and tags after frames are rearranged by the given edit.
+    ///
+    /// This is called by `PixelCanvasModel::edit_frames()` along with rearranging the pixels.
+    pub fn edit_frames(&mut self, edit: BandEdit) {
+        let count = usize::from(self.animation.frame_count());
+        self.animation
+            .set_frame_count(edit.applied_count(count) as u16);
+        self.frame_durations.edit(edit);
+        self.animation_tags.edit(edit);
+    }
+
+    pub fn frame_duration(&self, frame: usize) -> Duration {
+        self.frame_durations
+            .get(frame)
+            .unwrap_or_else(|| self.animation.frame_interval())
+    }
 }
 
 impl Serialize for ConfigModel {
@@ -60,6 +145,20 @@ impl Serialize for ConfigModel {
         self.gesture.serialize(writer).or_fail()?;
         self.background_color.serialize(writer).or_fail()?;
         self.apng.serialize(writer).or_fail()?;
+        self.persist_undo_history.serialize(writer).or_fail()?;
+        self.export_scale.serialize(writer).or_fail()?;
+        self.frame_durations.serialize(writer).or_fail()?;
+        self.animation_tags.serialize(writer).or_fail()?;
+        self.layer_attrs.serialize(writer).or_fail()?;
+        self.onion_skin.serialize(writer).or_fail()?;
+        self.symmetry.serialize(writer).or_fail()?;
+        self.pixel_perfect.serialize(writer).or_fail()?;
+        self.fill_mode.serialize(writer).or_fail()?;
+        self.fill_tolerance.serialize(writer).or_fail()?;
+        self.fill_diagonal.serialize(writer).or_fail()?;
+        self.fill_within_frame.serialize(writer).or_fail()?;
+        self.dither.serialize(writer).or_fail()?;
+        self.gradient.serialize(writer).or_fail()?;
         Ok(())
     }
 }
@@ -83,6 +182,20 @@ impl Deserialize for ConfigModel {
             gesture: Deserialize::deserialize_or_default(reader).or_fail()?,
             background_color: Deserialize::deserialize_or_default(reader).or_fail()?,
             apng: Deserialize::deserialize_or_default(reader).or_fail()?,
+            persist_undo_history: Deserialize::deserialize_or_default(reader).or_fail()?,
+            export_scale: Deserialize::deserialize_or_default(reader).or_fail()?,
+            frame_durations: Deserialize::deserialize_or_default(reader).or_fail()?,
+            animation_tags: Deserialize::deserialize_or_default(reader).or_fail()?,
+            layer_attrs: Deserialize::deserialize_or_default(reader).or_fail()?,
+            onion_skin: Deserialize::deserialize_or_default(reader).or_fail()?,
+            symmetry: Deserialize::deserialize_or_default(reader).or_fail()?,
+            pixel_perfect: Deserialize::deserialize_or_default(reader).or_fail()?,
+            fill_mode: Deserialize::deserialize_or_default(reader).or_fail()?,
+            fill_tolerance: Deserialize::deserialize_or_default(reader).or_fail()?,
+            fill_diagonal: Deserialize::deserialize_or_default(reader).or_fail()?,
+            fill_within_frame: Deserialize::deserialize_or_default(reader).or_fail()?,
+            dither: Deserialize::deserialize_or_default(reader).or_fail()?,
+            gradient: Deserialize::deserialize_or_default(reader).or_fail()?,
         })
     }
 }
@@ -369,6 +482,14 @@ impl FrameRegion {
         region
     }
 
+    /// Returns the region of the frame (of the layer) that contains the given position.
+    pub fn get_cell_region(self, position: PixelPosition) -> PixelRegion {
+        let size = self.region.size();
+        let frame = (position.x - self.region.start.x).div_euclid(size.width as i16);
+        let layer = (position.y - self.region.start.y).div_euclid(size.height as i16);
+        self.region.shift_x(frame).shift_y(layer)
+    }
+
     pub fn get_preview_region(self, config: &ConfigModel, frame: usize) -> PixelRegion {
         let layers = config.layer.enabled_count() - 1;
         let region = self.get_base_region();
@@ -453,6 +574,42 @@ impl Deserialize for FramePreviewScale {
     }
 }
 
+/// The integer scale applied to exported images (the workspace metadata is kept unscaled).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExportScale(u8);
+
+impl ExportScale {
+    pub const MIN: u8 = 1;
+    pub const MAX: u8 = 8;
+
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    pub fn set(&mut self, scale: u8) {
+        self.0 = scale.clamp(Self::MIN, Self::MAX);
+    }
+}
+
+impl Default for ExportScale {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl Serialize for ExportScale {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer).or_fail()
+    }
+}
+
+impl Deserialize for ExportScale {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let scale = u8::deserialize(reader).or_fail()?;
+        Ok(Self(scale.clamp(Self::MIN, Self::MAX)))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct FramePreview(bool);
 
@@ -488,6 +645,22 @@ impl Deserialize for FramePreview {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct MaxUndos(u32);
 
+impl MaxUndos {
+    pub const MIN: u32 = 1;
+    pub const MAX: u32 = 1000;
+
+    /// The upper bound of the total number of pixels held in the undo history.
+    pub const MAX_PIXELS: usize = 4_000_000;
+
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    pub fn set(&mut self, n: u32) {
+        self.0 = n.clamp(Self::MIN, Self::MAX);
+    }
+}
+
 impl Default for MaxUndos {
     fn default() -> Self {
         Self(100)
@@ -502,7 +675,8 @@ impl Serialize for MaxUndos {
 
 impl Deserialize for MaxUndos {
     fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
-        u32::deserialize(reader).map(Self).or_fail()
+        let n = u32::deserialize(reader).or_fail()?;
+        Ok(Self(n.clamp(Self::MIN, Self::MAX)))
     }
 }
 
@@ -536,6 +710,31 @@ impl Layer {
         self.count = n.clamp(Self::MIN, Self::MAX);
     }
 
+    /// Returns the index of the layer that contains the given position.
+    ///
+    /// If layers are disabled or the position is out of the layers, `None` is returned.
+    pub fn layer_index(
+        self,
+        frame: FrameRegion,
+        frames: u16,
+        position: PixelPosition,
+    ) -> Option<usize> {
+        let layers = self.enabled_count();
+        if layers == 1 {
+            return None;
+        }
+
+        let frame = frame.get_base_region();
+        let layer_region = PixelRegion::from_position_and_size(
+            frame.start,
+            PixelSize::from_wh(frame.size().width * frames, frame.size().height * layers),
+        );
+        if !layer_region.contains(position) {
+            return None;
+        }
+        Some(((position.y - frame.start.y) / frame.size().height as i16) as usize)
+    }
+
     pub fn for_each_lower_layer_pixel<F>(
         self,
         frame: FrameRegion,
@@ -681,6 +880,646 @@ impl Deserialize for Layer {
     }
 }
 
+/// An edit of the order of bands (i.e., layers or frames).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BandEdit {
+    /// Swaps two bands.
+    Swap(usize, usize),
+
+    /// Inserts an empty band at the given index.
+    Insert(usize),
+
+    /// Inserts a copy of the band at the given index right after it.
+    Duplicate(usize),
+
+    /// Deletes the band at the given index.
+    Delete(usize),
+
+    /// Moves the band at the first index to the second index.
+    Move(usize, usize),
+}
+
+impl BandEdit {
+    /// Returns whether this edit can be applied to `count` bands without exceeding `max`.
+    pub fn is_applicable(self, count: usize, max: usize) -> bool {
+        match self {
+            Self::Swap(a, b) | Self::Move(a, b) => a != b && a < count && b < count,
+            Self::Insert(i) => i <= count && count < max,
+            Self::Duplicate(i) => i < count && count < max,
+            Self::Delete(i) => i < count,
+        }
+    }
+
+    /// Returns the number of bands after this edit is applied to `count` bands.
+    pub fn applied_count(self, count: usize) -> usize {
+        match self {
+            Self::Swap(..) | Self::Move(..) => count,
+            Self::Insert(_) | Self::Duplicate(_) => count + 1,
+            Self::Delete(_) => count - 1,
+        }
+    }
+
+    /// Returns the indices that the band at the given index is moved (or copied) to.
+    pub fn destinations(self, index: usize) -> Vec<usize> {
+        match self {
+            Self::Swap(a, b) if index == a => vec![b],
+            Self::Swap(a, b) if index == b => vec![a],
+            Self::Insert(i) if index >= i => vec![index + 1],
+            Self::Duplicate(i) if index == i => vec![i, i + 1],
+            Self::Duplicate(i) if index > i => vec![index + 1],
+            Self::Delete(i) if index == i => vec![],
+            Self::Delete(i) if index > i => vec![index - 1],
+            Self::Move(from, to) if index == from => vec![to],
+            Self::Move(from, to) if from < index && index <= to => vec![index - 1],
+            Self::Move(from, to) if to <= index && index < from => vec![index + 1],
+            _ => vec![index],
+        }
+    }
+
+    /// Applies this edit to `items` (an inserted item is filled with `T::default()`).
+    pub fn apply<T: Clone + Default>(self, items: &mut Vec<T>) {
+        match self {
+            Self::Swap(a, b) => items.swap(a, b),
+            Self::Insert(i) => items.insert(i, T::default()),
+            Self::Duplicate(i) => items.insert(i + 1, items[i].clone()),
+            Self::Delete(i) => {
+                items.remove(i);
+            }
+            Self::Move(from, to) => {
+                let item = items.remove(from);
+                items.insert(to, item);
+            }
+        }
+    }
+}
+
+/// The part of [`ConfigModel`] that is rearranged along with the pixels by a [`BandEdit`].
+///
+/// Layer and frame edits record this before and after the edit
+/// so that undoing them restores it too.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BandConfig {
+    layer_count: u16,
+    layer_attrs: LayerAttributesTable,
+    frame_count: u16,
+    frame_durations: FrameDurations,
+    animation_tags: AnimationTags,
+}
+
+impl Serialize for BandConfig {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.layer_count.serialize(writer).or_fail()?;
+        self.layer_attrs.serialize(writer).or_fail()?;
+        self.frame_count.serialize(writer).or_fail()?;
+        self.frame_durations.serialize(writer).or_fail()?;
+        self.animation_tags.serialize(writer).or_fail()?;
+        Ok(())
+    }
+}
+
+impl Deserialize for BandConfig {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self {
+            layer_count: Deserialize::deserialize(reader).or_fail()?,
+            layer_attrs: Deserialize::deserialize(reader).or_fail()?,
+            frame_count: Deserialize::deserialize(reader).or_fail()?,
+            frame_durations: Deserialize::deserialize(reader).or_fail()?,
+            animation_tags: Deserialize::deserialize(reader).or_fail()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerAttributes {
+    pub hidden: bool,
+
+    /// Percentage.
+    pub opacity: u8,
+
+    /// If `true`, the pixels of the layer cannot be drawn or erased.
+    pub locked: bool,
+
+    /// How the layer is composited onto the lower layers.
+    pub blend_mode: BlendMode,
+}
+
+impl LayerAttributes {
+    pub const MAX_OPACITY: u8 = 100;
+
+    /// Applies the visibility and opacity to a pixel color of the layer.
+    pub fn apply(self, mut color: Rgba) -> Option<Rgba> {
+        if self.hidden || self.opacity == 0 {
+            return None;
+        }
+        color.a =
+            (u32::from(color.a) * u32::from(self.opacity) / u32::from(Self::MAX_OPACITY)) as u8;
+        Some(color)
+    }
+}
+
+impl Default for LayerAttributes {
+    fn default() -> Self {
+        Self {
+            hidden: false,
+            opacity: Self::MAX_OPACITY,
+            locked: false,
+            blend_mode: BlendMode::default(),
+        }
+    }
+}
+
+impl Serialize for LayerAttributes {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.hidden.serialize(writer).or_fail()?;
+        self.opacity.serialize(writer).or_fail()?;
+        self.locked.serialize(writer).or_fail()?;
+        self.blend_mode.serialize(writer).or_fail()?;
+        Ok(())
+    }
+}
+
+impl Deserialize for LayerAttributes {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self {
+            hidden: bool::deserialize(reader).or_fail()?,
+            opacity: u8::deserialize(reader).or_fail()?.min(Self::MAX_OPACITY),
+            locked: bool::deserialize(reader).or_fail()?,
+            blend_mode: BlendMode::deserialize(reader).or_fail()?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Add,
+    Subtract,
+    Darken,
+    Lighten,
+}
+
+impl BlendMode {
+    pub const ALL: [Self; 8] = [
+        Self::Normal,
+        Self::Multiply,
+        Self::Screen,
+        Self::Overlay,
+        Self::Add,
+        Self::Subtract,
+        Self::Darken,
+        Self::Lighten,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "NORMAL",
+            Self::Multiply => "MULTIPLY",
+            Self::Screen => "SCREEN",
+            Self::Overlay => "OVERLAY",
+            Self::Add => "ADD",
+            Self::Subtract => "SUBTRACT",
+            Self::Darken => "DARKEN",
+            Self::Lighten => "LIGHTEN",
+        }
+    }
+
+    /// Composites `src` (the upper layer color) onto `dst` (the lower layer color).
+    ///
+    /// As with the W3C compositing spec, the blended color is mixed with `src` according to the alpha of `dst`,
+    /// and then alpha-blended onto `dst`.
+    pub fn blend(self, src: Rgba, dst: Rgba) -> Rgba {
+        if self == Self::Normal || dst.a == 0 {
+            return src.alpha_blend(dst);
+        }
+
+        let dst_alpha = u32::from(dst.a);
+        let mix = |s: u8, d: u8| {
+            let blended = u32::from(self.blend_channel(s, d));
+            ((u32::from(s) * (0xFF - dst_alpha) + blended * dst_alpha) / 0xFF) as u8
+        };
+        let src = Rgba::new(
+            mix(src.r, dst.r),
+            mix(src.g, dst.g),
+            mix(src.b, dst.b),
+            src.a,
+        );
+        src.alpha_blend(dst)
+    }
+
+    fn blend_channel(self, s: u8, d: u8) -> u8 {
+        let (s, d) = (u32::from(s), u32::from(d));
+        let c = match self {
+            Self::Normal => s,
+            Self::Multiply => s * d / 0xFF,
+            Self::Screen => s + d - s * d / 0xFF,
+            Self::Overlay if d < 0x80 => 2 * s * d / 0xFF,
+            Self::Overlay => 0xFF - 2 * (0xFF - s) * (0xFF - d) / 0xFF,
+            Self::Add => (s + d).min(0xFF),
+            Self::Subtract => d.saturating_sub(s),
+            Self::Darken => s.min(d),
+            Self::Lighten => s.max(d),
+        };
+        c as u8
+    }
+}
+
+impl Serialize for BlendMode {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        (*self as u8).serialize(writer).or_fail()
+    }
+}
+
+impl Deserialize for BlendMode {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let n = u8::deserialize(reader).or_fail()?;
+        Self::ALL
+            .get(usize::from(n))
+            .copied()
+            .or_fail_with(|()| format!("unknown blend mode: {n}"))
+    }
+}
+
+/// Pixels marked by the FILL tool.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillMode {
+    /// The contiguous area of the clicked color.
+    #[default]
+    Area,
+
+    /// All pixels of the clicked color within the clicked frame (of the clicked layer).
+    Frame,
+
+    /// All pixels of the clicked color within the whole canvas.
+    Canvas,
+
+    /// The contiguous area of the pressed color, filled with a gradient along the dragged line.
+    Gradient,
+}
+
+impl FillMode {
+    pub const ALL: [Self; 4] = [Self::Area, Self::Frame, Self::Canvas, Self::Gradient];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Area => "AREA",
+            Self::Frame => "FRAME",
+            Self::Canvas => "CANVAS",
+            Self::Gradient => "GRADIENT",
+        }
+    }
+}
+
+impl Serialize for FillMode {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        (*self as u8).serialize(writer).or_fail()
+    }
+}
+
+impl Deserialize for FillMode {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let n = u8::deserialize(reader).or_fail()?;
+        Self::ALL
+            .get(usize::from(n))
+            .copied()
+            .or_fail_with(|()| format!("unknown fill mode: {n}"))
+    }
+}
+
+/// Ordered-dither pattern that limits the positions painted by the DRAW and FILL tools.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DitherPattern {
+    #[default]
+    Off,
+    Checker,
+    BayerTwo,
+    BayerFour,
+    Custom,
+}
+
+impl DitherPattern {
+    pub const ALL: [Self; 5] = [
+        Self::Off,
+        Self::Checker,
+        Self::BayerTwo,
+        Self::BayerFour,
+        Self::Custom,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Off => "OFF",
+            Self::Checker => "CHECKER",
+            Self::BayerTwo => "BAYER TWO",
+            Self::BayerFour => "BAYER FOUR",
+            Self::Custom => "CUSTOM",
+        }
+    }
+}
+
+impl Serialize for DitherPattern {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        (*self as u8).serialize(writer).or_fail()
+    }
+}
+
+impl Deserialize for DitherPattern {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let n = u8::deserialize(reader).or_fail()?;
+        Self::ALL
+            .get(usize::from(n))
+            .copied()
+            .or_fail_with(|()| format!("unknown dither pattern: {n}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dither {
+    pub pattern: DitherPattern,
+
+    /// Percentage of the positions painted with the drawing color (only used by the Bayer patterns).
+    pub density: u8,
+
+    /// 4x4 tile of the custom pattern (the bit `y * 4 + x` is set if the position is painted).
+    pub tile: u16,
+
+    /// If `true`, the positions not painted with the drawing color are painted with `second_color`.
+    pub two_color: bool,
+    pub second_color: Rgba,
+}
+
+impl Dither {
+    pub const MAX_DENSITY: u8 = 100;
+    pub const TILE_SIZE: i16 = 4;
+
+    const BAYER_TWO: [[u8; 2]; 2] = [[0, 2], [3, 1]];
+    const BAYER_FOUR: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+    pub fn is_enabled(self) -> bool {
+        self.pattern != DitherPattern::Off
+    }
+
+    /// Returns whether the pattern (anchored to the canvas origin) paints the given position with the drawing color.
+    pub fn is_primary(self, position: PixelPosition) -> bool {
+        let bayer = |n: i16, value: u8| {
+            let n = u32::from(n.unsigned_abs());
+            (u32::from(value) * 2 + 1) * 100 < u32::from(self.density) * 2 * n * n
+        };
+        match self.pattern {
+            DitherPattern::Off => true,
+            DitherPattern::Checker => (position.x + position.y).rem_euclid(2) == 0,
+            DitherPattern::BayerTwo => {
+                let x = position.x.rem_euclid(2) as usize;
+                let y = position.y.rem_euclid(2) as usize;
+                bayer(2, Self::BAYER_TWO[y][x])
+            }
+            DitherPattern::BayerFour => {
+                let x = position.x.rem_euclid(4) as usize;
+                let y = position.y.rem_euclid(4) as usize;
+                bayer(4, Self::BAYER_FOUR[y][x])
+            }
+            DitherPattern::Custom => {
+                let x = position.x.rem_euclid(Self::TILE_SIZE);
+                let y = position.y.rem_euclid(Self::TILE_SIZE);
+                self.tile & (1 << (y * Self::TILE_SIZE + x)) != 0
+            }
+        }
+    }
+
+    /// Returns the color to paint at the given position (`None` means the position is not painted).
+    pub fn color(self, position: PixelPosition, color: Rgba) -> Option<Rgba> {
+        if self.is_primary(position) {
+            Some(color)
+        } else if self.two_color {
+            Some(self.second_color)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self {
+            pattern: DitherPattern::Off,
+            density: 50,
+            tile: 0b1010_0101_1010_0101,
+            two_color: false,
+            second_color: Rgba::new(255, 255, 255, 255),
+        }
+    }
+}
+
+impl Serialize for Dither {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.pattern.serialize(writer).or_fail()?;
+        self.density.serialize(writer).or_fail()?;
+        self.tile.serialize(writer).or_fail()?;
+        self.two_color.serialize(writer).or_fail()?;
+        self.second_color.serialize(writer).or_fail()?;
+        Ok(())
+    }
+}
+
+impl Deserialize for Dither {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self {
+            pattern: DitherPattern::deserialize(reader).or_fail()?,
+            density: u8::deserialize(reader).or_fail()?,
+            tile: u16::deserialize(reader).or_fail()?,
+            two_color: bool::deserialize(reader).or_fail()?,
+            second_color: Rgba::deserialize(reader).or_fail()?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GradientStyle {
+    #[default]
+    Banded,
+
+    /// Transitions between the steps with the 4x4 Bayer pattern.
+    Dithered,
+}
+
+impl GradientStyle {
+    pub const ALL: [Self; 2] = [Self::Banded, Self::Dithered];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Banded => "BANDED",
+            Self::Dithered => "DITHERED",
+        }
+    }
+}
+
+impl Serialize for GradientStyle {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        (*self as u8).serialize(writer).or_fail()
+    }
+}
+
+impl Deserialize for GradientStyle {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let n = u8::deserialize(reader).or_fail()?;
+        Self::ALL
+            .get(usize::from(n))
+            .copied()
+            .or_fail_with(|()| format!("unknown gradient style: {n}"))
+    }
+}
+
+/// Ramp of colors from the drawing color to `end_color`, used by the gradient fill mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gradient {
+    pub steps: u8,
+    pub style: GradientStyle,
+    pub end_color: Rgba,
+}
+
+impl Gradient {
+    pub const MIN_STEPS: u8 = 2;
+    pub const MAX_STEPS: u8 = 16;
+
+    /// Returns the color of the given position of the gradient from `start` (`from` color) to `end` (`to` color).
+    pub fn color(
+        self,
+        start: PixelPosition,
+        end: PixelPosition,
+        position: PixelPosition,
+        from: Rgba,
+        to: Rgba,
+    ) -> Rgba {
+        let steps = self.steps.clamp(Self::MIN_STEPS, Self::MAX_STEPS);
+        let max_step = f64::from(steps - 1);
+
+        // The position is projected onto the line from `start` to `end`.
+        let (dx, dy) = (f64::from(end.x - start.x), f64::from(end.y - start.y));
+        let (px, py) = (
+            f64::from(position.x - start.x),
+            f64::from(position.y - start.y),
+        );
+        let length = dx * dx + dy * dy;
+        let t = if length == 0.0 {
+            0.0
+        } else {
+            ((px * dx + py * dy) / length).clamp(0.0, 1.0)
+        };
+
+        let step = match self.style {
+            GradientStyle::Banded => (t * max_step).round(),
+            GradientStyle::Dithered => {
+                let x = position.x.rem_euclid(4) as usize;
+                let y = position.y.rem_euclid(4) as usize;
+                let threshold = (f64::from(Dither::BAYER_FOUR[y][x]) + 0.5) / 16.0;
+                let scaled = t * max_step;
+                if scaled.fract() > threshold {
+                    scaled.ceil()
+                } else {
+                    scaled.floor()
+                }
+            }
+        };
+
+        let ratio = step / max_step;
+        let lerp =
+            |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * ratio).round() as u8;
+        Rgba::new(
+            lerp(from.r, to.r),
+            lerp(from.g, to.g),
+            lerp(from.b, to.b),
+            lerp(from.a, to.a),
+        )
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self {
+            steps: 4,
+            style: GradientStyle::Banded,
+            end_color: Rgba::new(255, 255, 255, 255),
+        }
+    }
+}
+
+impl Serialize for Gradient {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.steps.serialize(writer).or_fail()?;
+        self.style.serialize(writer).or_fail()?;
+        self.end_color.serialize(writer).or_fail()?;
+        Ok(())
+    }
+}
+
+impl Deserialize for Gradient {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self {
+            steps: u8::deserialize(reader).or_fail()?,
+            style: GradientStyle::deserialize(reader).or_fail()?,
+            end_color: Rgba::deserialize(reader).or_fail()?,
+        })
+    }
+}
+
+/// Attributes of each layer (the index 0 is the bottom layer).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerAttributesTable([LayerAttributes; Layer::MAX as usize]);
+
+impl LayerAttributesTable {
+    pub fn get(&self, layer: usize) -> LayerAttributes {
+        self.0.get(layer).copied().unwrap_or_default()
+    }
+
+    pub fn get_mut(&mut self, layer: usize) -> Option<&mut LayerAttributes> {
+        self.0.get_mut(layer)
+    }
+
+    /// Returns the attributes of the layer that contains the given position.
+    pub fn get_by_position(
+        &self,
+        layer: Layer,
+        frame: FrameRegion,
+        frames: u16,
+        position: PixelPosition,
+    ) -> LayerAttributes {
+        layer
+            .layer_index(frame, frames, position)
+            .map(|i| self.get(i))
+            .unwrap_or_default()
+    }
+
+    /// Moves the attributes along with the layers rearranged by the given edit.
+    pub fn edit(&mut self, edit: BandEdit) {
+        let mut attrs = self.0.to_vec();
+        edit.apply(&mut attrs);
+        attrs.resize(self.0.len(), LayerAttributes::default());
+        self.0.copy_from_slice(&attrs);
+    }
+}
+
+impl Serialize for LayerAttributesTable {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        for attrs in &self.0 {
+            attrs.serialize(writer).or_fail()?;
+        }
+        Ok(())
+    }
+}
+
+impl Deserialize for LayerAttributesTable {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut table = Self::default();
+        for attrs in &mut table.0 {
+            *attrs = LayerAttributes::deserialize(reader).or_fail()?;
+        }
+        Ok(table)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Animation {
     enabled: bool,
@@ -757,6 +1596,482 @@ impl Deserialize for Animation {
     }
 }
 
+/// Per-frame durations in milliseconds.
+///
+/// Frames that don't have an entry are played at `Animation::fps()`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct FrameDurations(BTreeMap<u16, u16>);
+
+impl FrameDurations {
+    pub const MIN_MILLIS: u16 = 1;
+    pub const MAX_MILLIS: u16 = 9999;
+
+    pub fn get(&self, frame: usize) -> Option<Duration> {
+        self.get_millis(frame)
+            .map(|millis| Duration::from_millis(u64::from(millis)))
+    }
+
+    pub fn get_millis(&self, frame: usize) -> Option<u16> {
+        let frame = u16::try_from(frame).ok()?;
+        self.0.get(&frame).copied()
+    }
+
+    pub fn set_millis(&mut self, frame: usize, millis: Option<u16>) {
+        let Ok(frame) = u16::try_from(frame) else {
+            return;
+        };
+        if let Some(millis) = millis {
+            let millis = millis.clamp(Self::MIN_MILLIS, Self::MAX_MILLIS);
+            self.0.insert(frame, millis);
+        } else {
+            self.0.remove(&frame);
+        }
+    }
+
+    /// Moves the durations along with the frames rearranged by the given edit.
+    pub fn edit(&mut self, edit: BandEdit) {
+        let old = std::mem::take(&mut self.0);
+        for (frame, millis) in old {
+            for frame in edit.destinations(usize::from(frame)) {
+                self.set_millis(frame, Some(millis));
+            }
+        }
+    }
+}
+
+impl Serialize for FrameDurations {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        (self.0.len() as u16).serialize(writer).or_fail()?;
+        for (frame, millis) in &self.0 {
+            frame.serialize(writer).or_fail()?;
+            millis.serialize(writer).or_fail()?;
+        }
+        Ok(())
+    }
+}
+
+impl Deserialize for FrameDurations {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let n = u16::deserialize(reader).or_fail()?;
+        let mut durations = Self::default();
+        for _ in 0..n {
+            let frame = u16::deserialize(reader).or_fail()?;
+            let millis = u16::deserialize(reader).or_fail()?;
+            durations.set_millis(usize::from(frame), Some(millis));
+        }
+        Ok(durations)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoopDirection {
+    #[default]
+    Forward,
+    Reverse,
+    PingPong,
+}
+
+impl LoopDirection {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Forward => "forward",
+            Self::Reverse => "reverse",
+            Self::PingPong => "pingpong",
+        }
+    }
+}
+
+impl std::str::FromStr for LoopDirection {
+    type Err = orfail::Failure;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "forward" => Ok(Self::Forward),
+            "reverse" => Ok(Self::Reverse),
+            "pingpong" | "ping-pong" => Ok(Self::PingPong),
+            _ => Err(orfail::Failure::new(format!(
+                "unknown loop direction: {s:?}"
+            ))),
+        }
+    }
+}
+
+impl Serialize for LoopDirection {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        (*self as u8).serialize(writer).or_fail()
+    }
+}
+
+impl Deserialize for LoopDirection {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        match u8::deserialize(reader).or_fail()? {
+            0 => Ok(Self::Forward),
+            1 => Ok(Self::Reverse),
+            2 => Ok(Self::PingPong),
+            n => Err(orfail::Failure::new(format!("unknown loop direction: {n}"))),
+        }
+    }
+}
+
+/// A named range of animation frames (e.g., "idle 0-3" or "run 4-9 pingpong").
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnimationTag {
+    pub name: String,
+    pub start: u16,
+
+    /// Inclusive.
+    pub end: u16,
+
+    pub direction: LoopDirection,
+}
+
+impl AnimationTag {
+    /// Returns the frames of one loop of this tag in the playing order.
+    pub fn frames(&self) -> Vec<usize> {
+        let start = usize::from(self.start);
+        let end = usize::from(self.end);
+        match self.direction {
+            LoopDirection::Forward => (start..=end).collect(),
+            LoopDirection::Reverse => (start..=end).rev().collect(),
+            LoopDirection::PingPong => (start..=end).chain((start + 1..end).rev()).collect(),
+        }
+    }
+}
+
+impl std::str::FromStr for AnimationTag {
+    type Err = orfail::Failure;
+
+    /// Parses "NAME START-END [DIRECTION]".
+    fn from_str(s: &str) -> Result<Self> {
+        let mut tokens = s.split_whitespace();
+        let name = tokens.next().or_fail()?.to_owned();
+        let (start, end) = tokens.next().or_fail()?.split_once('-').or_fail()?;
+        let start = start.parse::<u16>().or_fail()?;
+        let end = end.parse::<u16>().or_fail()?;
+        (start <= end).or_fail()?;
+        let direction = tokens
+            .next()
+            .map(|t| t.parse::<LoopDirection>())
+            .transpose()
+            .or_fail()?
+            .unwrap_or_default();
+        tokens.next().is_none().or_fail()?;
+        Ok(Self {
+            name,
+            start,
+            end,
+            direction,
+        })
+    }
+}
+
+impl Serialize for AnimationTag {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.name.serialize(writer).or_fail()?;
+        self.start.serialize(writer).or_fail()?;
+        self.end.serialize(writer).or_fail()?;
+        self.direction.serialize(writer).or_fail()?;
+        Ok(())
+    }
+}
+
+impl Deserialize for AnimationTag {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self {
+            name: String::deserialize(reader).or_fail()?,
+            start: u16::deserialize(reader).or_fail()?,
+            end: u16::deserialize(reader).or_fail()?,
+            direction: LoopDirection::deserialize(reader).or_fail()?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct AnimationTags {
+    tags: Vec<AnimationTag>,
+
+    // The tag played by the preview.
+    selected: Option<usize>,
+}
+
+impl AnimationTags {
+    pub const MAX: usize = 100;
+
+    pub fn tags(&self) -> &[AnimationTag] {
+        &self.tags
+    }
+
+    /// Adds a tag (or replaces the one that has the same name) and selects it.
+    pub fn add(&mut self, tag: AnimationTag) -> Result<()> {
+        if let Some(i) = self.tags.iter().position(|t| t.name == tag.name) {
+            self.tags[i] = tag;
+            self.selected = Some(i);
+        } else {
+            (self.tags.len() < Self::MAX)
+                .or_fail_with(|()| format!("too many animation tags (max {})", Self::MAX))?;
+            self.tags.push(tag);
+            self.selected = Some(self.tags.len() - 1);
+        }
+        Ok(())
+    }
+
+    pub fn remove_selected(&mut self) {
+        if let Some(i) = self.selected.take() {
+            self.tags.remove(i);
+        }
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn selected(&self) -> Option<&AnimationTag> {
+        self.tags.get(self.selected?)
+    }
+
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index.filter(|&i| i < self.tags.len());
+    }
+
+    /// Updates the frame ranges of the tags after frames are inserted or deleted by the given edit.
+    ///
+    /// Tags that only cover a deleted frame are removed.
+    /// Swapping or moving frames doesn't change the ranges.
+    pub fn edit(&mut self, edit: BandEdit) {
+        let selected = self.selected.and_then(|i| self.tags.get(i)).cloned();
+        self.tags.retain_mut(|tag| {
+            let (start, end) = (tag.start as usize, tag.end as usize);
+            let (start, end) = match edit {
+                BandEdit::Swap(..) | BandEdit::Move(..) => (start, end),
+                BandEdit::Insert(i) => {
+                    (start + usize::from(start >= i), end + usize::from(end >= i))
+                }
+                BandEdit::Duplicate(i) => {
+                    (start + usize::from(start > i), end + usize::from(end >= i))
+                }
+                BandEdit::Delete(i) if start == i && end == i => return false,
+                BandEdit::Delete(i) => {
+                    (start - usize::from(start > i), end - usize::from(end >= i))
+                }
+            };
+            tag.start = start as u16;
+            tag.end = end as u16;
+            true
+        });
+        self.selected = selected
+            .and_then(|selected| self.tags.iter().position(|tag| tag.name == selected.name));
+    }
+}
+
+impl Serialize for AnimationTags {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.tags.serialize(writer).or_fail()?;
+        self.selected.serialize(writer).or_fail()?;
+        Ok(())
+    }
+}
+
+impl Deserialize for AnimationTags {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut tags = Self {
+            tags: Vec::deserialize(reader).or_fail()?,
+            selected: None,
+        };
+        tags.select(Option::deserialize(reader).or_fail()?);
+        Ok(tags)
+    }
+}
+
+/// Settings to show the neighbouring frames of the current frame behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OnionSkin {
+    pub prev_frames: u8,
+    pub next_frames: u8,
+
+    /// Percent.
+    pub opacity: u8,
+
+    /// If `true`, previous frames are tinted red and next frames are tinted blue.
+    pub tint: bool,
+}
+
+impl OnionSkin {
+    pub const MAX_FRAMES: u8 = 5;
+    pub const MAX_OPACITY: u8 = 100;
+
+    const PREV_TINT: Rgba = Rgba::new(255, 0, 0, 255);
+    const NEXT_TINT: Rgba = Rgba::new(0, 0, 255, 255);
+
+    pub fn is_enabled(self) -> bool {
+        (self.prev_frames > 0 || self.next_frames > 0) && self.opacity > 0
+    }
+
+    /// Returns the offsets of the frames to be shown relative to the current frame (the farthest first).
+    pub fn frame_offsets(self) -> Vec<i16> {
+        let mut offsets = (1..=i16::from(self.prev_frames))
+            .map(|i| -i)
+            .chain(1..=i16::from(self.next_frames))
+            .collect::<Vec<_>>();
+        offsets.sort_by_key(|i| std::cmp::Reverse(i.abs()));
+        offsets
+    }
+
+    /// Converts a pixel color of the frame at the given offset into the color to be shown.
+    ///
+    /// The farther the frame is from the current frame, the more transparent the color is.
+    pub fn apply(self, mut color: Rgba, offset: i16) -> Rgba {
+        if self.tint {
+            let tint = if offset < 0 {
+                Self::PREV_TINT
+            } else {
+                Self::NEXT_TINT
+            };
+            color.r = ((u16::from(color.r) + u16::from(tint.r)) / 2) as u8;
+            color.g = ((u16::from(color.g) + u16::from(tint.g)) / 2) as u8;
+            color.b = ((u16::from(color.b) + u16::from(tint.b)) / 2) as u8;
+        }
+        let alpha = u32::from(color.a) * u32::from(self.opacity)
+            / u32::from(Self::MAX_OPACITY)
+            / u32::from(offset.unsigned_abs());
+        color.a = alpha as u8;
+        color
+    }
+}
+
+impl Default for OnionSkin {
+    fn default() -> Self {
+        Self {
+            prev_frames: 0,
+            next_frames: 0,
+            opacity: 30,
+            tint: true,
+        }
+    }
+}
+
+impl Serialize for OnionSkin {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.prev_frames.serialize(writer).or_fail()?;
+        self.next_frames.serialize(writer).or_fail()?;
+        self.opacity.serialize(writer).or_fail()?;
+        self.tint.serialize(writer).or_fail()?;
+        Ok(())
+    }
+}
+
+impl Deserialize for OnionSkin {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self {
+            prev_frames: u8::deserialize(reader).or_fail()?.min(Self::MAX_FRAMES),
+            next_frames: u8::deserialize(reader).or_fail()?.min(Self::MAX_FRAMES),
+            opacity: u8::deserialize(reader).or_fail()?.min(Self::MAX_OPACITY),
+            tint: bool::deserialize(reader).or_fail()?,
+        })
+    }
+}
+
+/// Settings to mirror drawn, erased and filled pixels within each frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symmetry {
+    /// Mirrors pixels across the vertical axis.
+    pub horizontal: bool,
+
+    /// Mirrors pixels across the horizontal axis.
+    pub vertical: bool,
+
+    /// Offset of the vertical axis from the left edge of a frame (`None` means the center of the frame).
+    pub axis_x: Option<u16>,
+
+    /// Offset of the horizontal axis from the top edge of a frame (`None` means the center of the frame).
+    pub axis_y: Option<u16>,
+}
+
+impl Symmetry {
+    pub fn is_enabled(self) -> bool {
+        self.horizontal || self.vertical
+    }
+
+    /// Returns the doubled x and y coordinates of the axes of the frame that contains the given position.
+    ///
+    /// The coordinates are doubled so that an axis can pass through the middle of pixels
+    /// (e.g., the center of a frame that has an odd width).
+    pub fn doubled_axes(self, frame: PixelRegion, position: PixelPosition) -> (i16, i16) {
+        let cell = Self::cell_region(frame, position);
+        let (left, top) = (cell.start.x, cell.start.y);
+        let width = frame.size().width as i16;
+        let height = frame.size().height as i16;
+        let x = self
+            .axis_x
+            .map_or(left * 2 + width, |offset| (left + offset as i16) * 2);
+        let y = self
+            .axis_y
+            .map_or(top * 2 + height, |offset| (top + offset as i16) * 2);
+        (x, y)
+    }
+
+    // Returns the region of the frame (or the neighbouring frame or layer cell) that contains the given position.
+    fn cell_region(frame: PixelRegion, position: PixelPosition) -> PixelRegion {
+        let size = frame.size();
+        let (width, height) = (size.width as i16, size.height as i16);
+        let left = frame.start.x + (position.x - frame.start.x).div_euclid(width) * width;
+        let top = frame.start.y + (position.y - frame.start.y).div_euclid(height) * height;
+        PixelRegion::from_position_and_size(PixelPosition::from_xy(left, top), size)
+    }
+
+    /// Clamps the axes so that they are within a frame of the given size.
+    pub fn clamp_axes(&mut self, frame_size: PixelSize) {
+        self.axis_x = self.axis_x.map(|x| x.min(frame_size.width));
+        self.axis_y = self.axis_y.map(|y| y.min(frame_size.height));
+    }
+
+    /// Returns the given position and its mirrored positions (without duplicates).
+    ///
+    /// Mirrored positions outside the frame that contains the given position are dropped
+    /// (e.g., if the axis is not at the center of the frame).
+    pub fn positions(self, frame: PixelRegion, position: PixelPosition) -> Vec<PixelPosition> {
+        let (x2, y2) = self.doubled_axes(frame, position);
+        let mirrored_x = x2 - 1 - position.x;
+        let mirrored_y = y2 - 1 - position.y;
+
+        let mut positions = vec![position];
+        if self.horizontal {
+            positions.push(PixelPosition::from_xy(mirrored_x, position.y));
+        }
+        if self.vertical {
+            positions.push(PixelPosition::from_xy(position.x, mirrored_y));
+        }
+        if self.horizontal && self.vertical {
+            positions.push(PixelPosition::from_xy(mirrored_x, mirrored_y));
+        }
+        let cell = Self::cell_region(frame, position);
+        positions.retain(|&p| cell.contains(p));
+        positions.sort();
+        positions.dedup();
+        positions
+    }
+}
+
+impl Serialize for Symmetry {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.horizontal.serialize(writer).or_fail()?;
+        self.vertical.serialize(writer).or_fail()?;
+        self.axis_x.serialize(writer).or_fail()?;
+        self.axis_y.serialize(writer).or_fail()?;
+        Ok(())
+    }
+}
+
+impl Deserialize for Symmetry {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        Ok(Self {
+            horizontal: bool::deserialize(reader).or_fail()?,
+            vertical: bool::deserialize(reader).or_fail()?,
+            axis_x: Option::deserialize(reader).or_fail()?,
+            axis_y: Option::deserialize(reader).or_fail()?,
+        })
+    }
+}
+
 // TODO: Remove this struct in the future version
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct FingerMode {
@@ -807,3 +2122,201 @@ impl Deserialize for FingerMode {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn animation_tag_works() -> Result<()> {
+        let tag: AnimationTag = "idle 0-3".parse()?;
+        assert_eq!(tag.direction, LoopDirection::Forward);
+        assert_eq!(tag.frames(), [0, 1, 2, 3]);
+
+        let tag: AnimationTag = "run 4-6 reverse".parse()?;
+        assert_eq!(tag.frames(), [6, 5, 4]);
+
+        let tag: AnimationTag = "blink 4-7 pingpong".parse()?;
+        assert_eq!(tag.frames(), [4, 5, 6, 7, 6, 5]);
+
+        assert!("idle".parse::<AnimationTag>().is_err());
+        assert!("idle 3-0".parse::<AnimationTag>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn animation_tags_edit_works() -> Result<()> {
+        let mut tags = AnimationTags::default();
+        tags.add("idle 0-1".parse()?)?;
+        tags.add("run 2-4".parse()?)?;
+        tags.add("jump 5-5".parse()?)?;
+        let ranges = |tags: &AnimationTags| {
+            tags.tags()
+                .iter()
+                .map(|tag| (tag.start, tag.end))
+                .collect::<Vec<_>>()
+        };
+
+        tags.edit(BandEdit::Insert(2));
+        assert_eq!(ranges(&tags), [(0, 1), (3, 5), (6, 6)]);
+
+        tags.edit(BandEdit::Duplicate(4));
+        assert_eq!(ranges(&tags), [(0, 1), (3, 6), (7, 7)]);
+
+        tags.edit(BandEdit::Delete(7));
+        assert_eq!(ranges(&tags), [(0, 1), (3, 6)]);
+        assert_eq!(tags.selected_index(), None);
+
+        let mut durations = FrameDurations::default();
+        durations.set_millis(1, Some(100));
+        durations.edit(BandEdit::Move(1, 3));
+        assert_eq!(durations.get_millis(1), None);
+        assert_eq!(durations.get_millis(3), Some(100));
+        Ok(())
+    }
+
+    #[test]
+    fn symmetry_works() {
+        let frame = PixelRegion::new(PixelPosition::from_xy(0, 0), PixelPosition::from_xy(8, 5));
+        let mut symmetry = Symmetry {
+            horizontal: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            symmetry.positions(frame, PixelPosition::from_xy(1, 1)),
+            [PixelPosition::from_xy(1, 1), PixelPosition::from_xy(6, 1)]
+        );
+
+        // The second frame.
+        assert_eq!(
+            symmetry.positions(frame, PixelPosition::from_xy(9, 1)),
+            [PixelPosition::from_xy(9, 1), PixelPosition::from_xy(14, 1)]
+        );
+
+        // The center of an odd height.
+        symmetry.horizontal = false;
+        symmetry.vertical = true;
+        assert_eq!(
+            symmetry.positions(frame, PixelPosition::from_xy(1, 2)),
+            [PixelPosition::from_xy(1, 2)]
+        );
+
+        symmetry.horizontal = true;
+        symmetry.axis_x = Some(2);
+        assert_eq!(
+            symmetry.positions(frame, PixelPosition::from_xy(1, 0)),
+            [
+                PixelPosition::from_xy(1, 0),
+                PixelPosition::from_xy(2, 0),
+                PixelPosition::from_xy(1, 4),
+                PixelPosition::from_xy(2, 4),
+            ]
+        );
+
+        // Mirrored positions outside the frame are dropped.
+        symmetry.vertical = false;
+        assert_eq!(
+            symmetry.positions(frame, PixelPosition::from_xy(6, 0)),
+            [PixelPosition::from_xy(6, 0)]
+        );
+
+        symmetry.axis_x = Some(20);
+        symmetry.clamp_axes(frame.size());
+        assert_eq!(symmetry.axis_x, Some(8));
+    }
+
+    #[test]
+    fn dither_works() {
+        let painted = |dither: Dither| {
+            (0..4)
+                .flat_map(|y| (0..4).map(move |x| PixelPosition::from_xy(x, y)))
+                .filter(|&p| dither.is_primary(p))
+                .count()
+        };
+
+        let mut dither = Dither {
+            pattern: DitherPattern::Checker,
+            ..Default::default()
+        };
+        assert_eq!(painted(dither), 8);
+        assert!(dither.is_primary(PixelPosition::from_xy(-1, -1)));
+        assert!(!dither.is_primary(PixelPosition::from_xy(-1, 0)));
+
+        dither.pattern = DitherPattern::BayerFour;
+        for (density, expected) in [(0, 0), (25, 4), (50, 8), (100, 16)] {
+            dither.density = density;
+            assert_eq!(painted(dither), expected);
+        }
+
+        dither.pattern = DitherPattern::Custom;
+        dither.tile = 0b1;
+        assert_eq!(painted(dither), 1);
+        assert_eq!(
+            dither.color(PixelPosition::from_xy(4, 4), Rgba::new(0, 0, 0, 255)),
+            Some(Rgba::new(0, 0, 0, 255))
+        );
+        assert_eq!(
+            dither.color(PixelPosition::from_xy(1, 0), Rgba::new(0, 0, 0, 255)),
+            None
+        );
+
+        dither.two_color = true;
+        assert_eq!(
+            dither.color(PixelPosition::from_xy(1, 0), Rgba::new(0, 0, 0, 255)),
+            Some(dither.second_color)
+        );
+    }
+
+    #[test]
+    fn gradient_works() {
+        let black = Rgba::new(0, 0, 0, 255);
+        let white = Rgba::new(255, 255, 255, 255);
+        let start = PixelPosition::from_xy(0, 0);
+        let end = PixelPosition::from_xy(9, 0);
+        let red = |gradient: Gradient, x: i16, y: i16| {
+            gradient
+                .color(start, end, PixelPosition::from_xy(x, y), black, white)
+                .r
+        };
+
+        let mut gradient = Gradient {
+            steps: 4,
+            style: GradientStyle::Banded,
+            ..Gradient::default()
+        };
+        assert_eq!(red(gradient, -5, 0), 0);
+        assert_eq!(red(gradient, 0, 3), 0);
+        assert_eq!(red(gradient, 3, 3), 85);
+        assert_eq!(red(gradient, 6, 0), 170);
+        assert_eq!(red(gradient, 20, 0), 255);
+
+        // The adjacent steps are mixed.
+        gradient.style = GradientStyle::Dithered;
+        let reds = (0..4).map(|y| red(gradient, 4, y)).collect::<Vec<_>>();
+        assert_eq!(reds, [170, 85, 170, 85]);
+    }
+
+    #[test]
+    fn blend_mode_works() {
+        let src = Rgba::new(200, 100, 0, 255);
+        let dst = Rgba::new(255, 128, 64, 255);
+        assert_eq!(BlendMode::Normal.blend(src, dst), src);
+        assert_eq!(
+            BlendMode::Multiply.blend(src, dst),
+            Rgba::new(200, 50, 0, 255)
+        );
+        assert_eq!(
+            BlendMode::Screen.blend(src, dst),
+            Rgba::new(255, 178, 64, 255)
+        );
+        assert_eq!(BlendMode::Add.blend(src, dst), Rgba::new(255, 228, 64, 255));
+        assert_eq!(
+            BlendMode::Darken.blend(src, dst),
+            Rgba::new(200, 100, 0, 255)
+        );
+
+        // Blend modes have no effect on transparent backdrops.
+        let dst = Rgba::new(0, 0, 0, 0);
+        assert_eq!(BlendMode::Multiply.blend(src, dst), src);
+    }
+}
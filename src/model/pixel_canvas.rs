@@ -1,12 +1,15 @@
-use super::config::{ConfigModel, FrameRegion, Layer};
+use super::config::{
+    Animation, BandConfig, BandEdit, ConfigModel, FrameRegion, Layer, LayerAttributesTable,
+    MaxUndos,
+};
 use crate::{
-    pixel::{Pixel, PixelPosition, PixelRegion},
+    pixel::{Pixel, PixelPosition, PixelRegion, PixelSize},
     serialize::{Deserialize, Serialize},
 };
 use orfail::{OrFail, Result};
 use pagurus::image::Rgba;
 use std::{
-    collections::{BTreeMap, BTreeSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
     io::{Read, Write},
 };
 
@@ -17,6 +20,7 @@ pub struct PixelCanvasModel {
     pixels: Pixels,
 
     // The following fields are not serialized / deserialized
+    command_log_pixels: usize,
     dirty_positions: BTreeSet<PixelPosition>,
     state_version: i64,
 }
@@ -60,12 +64,13 @@ impl PixelCanvasModel {
         Ok(())
     }
 
+    /// Returns `false` if nothing is changed because some of the pixels are in locked layers.
     pub fn erase_and_draw_pixels(
         &mut self,
         config: &ConfigModel,
         erase_pixels: impl Iterator<Item = PixelPosition>,
         draw_pixels: impl Iterator<Item = Pixel>,
-    ) -> Result<()> {
+    ) -> Result<bool> {
         let mut command = PixelCanvasCommand::default();
         for position in erase_pixels {
             if let Some(color) = self.pixels.get_pixel(position) {
@@ -95,41 +100,175 @@ impl PixelCanvasModel {
         command.erase.dedup();
         command.draw.sort_by_key(|x| x.position);
 
+        // Filtering the pixels of locked layers one by one would delete or duplicate moved pixels,
+        // so moving pixels from or to locked layers is rejected as a whole.
+        if command
+            .erase
+            .iter()
+            .chain(&command.draw)
+            .any(|pixel| config.is_locked_position(pixel.position))
+        {
+            log::warn!("cannot move pixels from or to locked layers");
+            return Ok(false);
+        }
         self.apply_command(config, command).or_fail()?;
-        Ok(())
+        Ok(true)
     }
 
     pub fn replace_color(&mut self, config: &ConfigModel, old: Rgba, new: Rgba) -> Result<()> {
-        // TODO: optimize (e.g., to use cache to get target pixels)
-
         let mut command = PixelCanvasCommand::default();
-        for (&position, &color) in &self.pixels.pixels {
-            if color != old {
-                continue;
-            }
-
+        for position in self.color_positions(old) {
             command.erase.push(Pixel::new(position, old));
             command.draw.push(Pixel::new(position, new));
         }
-        command.draw.sort_by_key(|x| x.position);
-        command.erase.sort_by_key(|x| x.position);
 
         self.apply_command(config, command).or_fail()?;
         Ok(())
     }
 
-    fn apply_command(&mut self, config: &ConfigModel, command: PixelCanvasCommand) -> Result<()> {
-        if command.erase.is_empty() && command.draw.is_empty() {
+    /// Rearranges the layers and their config (e.g., attributes) as a single undoable command.
+    ///
+    /// Unlike drawing, this moves the pixels of locked layers too.
+    pub fn edit_layers(&mut self, config: &mut ConfigModel, edit: BandEdit) -> Result<()> {
+        let frame = config.frame.get_base_region();
+        let frames = config.animation.enabled_frame_count();
+        let layers = config.layer.enabled_count();
+        edit.is_applicable(usize::from(layers), usize::from(Layer::MAX))
+            .or_fail_with(|()| format!("inapplicable layer edit: {edit:?} ({layers} layers)"))?;
+
+        let step = PixelPosition::from_xy(0, frame.size().height as i16);
+        let mut command = self.band_edit_command(config, frames, layers, step, edit);
+
+        let mut edited = config.clone();
+        edited.edit_layers(edit);
+        command.band_config = Some(Box::new((config.band_config(), edited.band_config())));
+
+        self.push_command(config, command).or_fail()?;
+        config.set_band_config(&edited.band_config());
+        Ok(())
+    }
+
+    /// Rearranges the animation frames (of all the layers) and their config (e.g., durations)
+    /// as a single undoable command.
+    ///
+    /// Unlike drawing, this moves the pixels of locked layers too.
+    pub fn edit_frames(&mut self, config: &mut ConfigModel, edit: BandEdit) -> Result<()> {
+        let frame = config.frame.get_base_region();
+        let frames = config.animation.enabled_frame_count();
+        let layers = config.layer.enabled_count();
+        edit.is_applicable(usize::from(frames), usize::from(Animation::MAX_FRAME_COUNT))
+            .or_fail_with(|()| format!("inapplicable frame edit: {edit:?} ({frames} frames)"))?;
+
+        let step = PixelPosition::from_xy(frame.size().width as i16, 0);
+        let mut command = self.band_edit_command(config, frames, layers, step, edit);
+
+        let mut edited = config.clone();
+        edited.edit_frames(edit);
+        command.band_config = Some(Box::new((config.band_config(), edited.band_config())));
+
+        self.push_command(config, command).or_fail()?;
+        config.set_band_config(&edited.band_config());
+        Ok(())
+    }
+
+    // `step` is the offset between adjacent bands (i.e., layers or frames).
+    fn band_edit_command(
+        &self,
+        config: &ConfigModel,
+        frames: u16,
+        layers: u16,
+        step: PixelPosition,
+        edit: BandEdit,
+    ) -> PixelCanvasCommand {
+        let frame = config.frame.get_base_region();
+        let region = PixelRegion::from_position_and_size(
+            frame.start,
+            PixelSize::from_wh(frame.size().width * frames, frame.size().height * layers),
+        );
+        let mut command = PixelCanvasCommand::new();
+        for (&position, &color) in &self.pixels.pixels {
+            if !region.contains(position) {
+                continue;
+            }
+
+            let band = if step.x == 0 {
+                (position.y - frame.start.y) / step.y
+            } else {
+                (position.x - frame.start.x) / step.x
+            } as usize;
+            let destinations = edit.destinations(band);
+            if destinations == [band] {
+                continue;
+            }
+
+            command.erase.push(Pixel::new(position, color));
+            for destination in destinations {
+                let n = destination as i16 - band as i16;
+                let position =
+                    PixelPosition::from_xy(position.x + step.x * n, position.y + step.y * n);
+                command.draw.push(Pixel::new(position, color));
+            }
+        }
+
+        // Inserting or duplicating a band moves the last band beyond `region`,
+        // where pixels may already exist. They are overwritten.
+        for pixel in &command.draw {
+            if region.contains(pixel.position) {
+                continue;
+            }
+            if let Some(color) = self.pixels.get_pixel(pixel.position) {
+                command.erase.push(Pixel::new(pixel.position, color));
+            }
+        }
+        command.erase.sort_by_key(|x| x.position);
+        command.draw.sort_by_key(|x| x.position);
+        command
+    }
+
+    fn apply_command(
+        &mut self,
+        config: &ConfigModel,
+        mut command: PixelCanvasCommand,
+    ) -> Result<()> {
+        // Locked layers are not modified.
+        command
+            .erase
+            .retain(|pixel| !config.is_locked_position(pixel.position));
+        command
+            .draw
+            .retain(|pixel| !config.is_locked_position(pixel.position));
+        self.push_command(config, command).or_fail()
+    }
+
+    // Note that the band config of the command (if any) is not applied to `config` by this method.
+    fn push_command(&mut self, config: &ConfigModel, command: PixelCanvasCommand) -> Result<()> {
+        if command.erase.is_empty() && command.draw.is_empty() && command.band_config.is_none() {
             return Ok(());
         }
 
-        self.command_log.truncate(self.command_log_tail);
+        for command in self.command_log.drain(self.command_log_tail..) {
+            self.command_log_pixels -= command.pixel_count();
+        }
+        self.command_log_pixels += command.pixel_count();
         self.command_log.push_back(command);
-        self.redo_command(config).or_fail()?;
+        self.redo_pixels(config).or_fail()?;
+        self.forget_exceeded_commands(config);
 
         Ok(())
     }
 
+    /// Drops the oldest commands until the undo history fits in the limits.
+    ///
+    /// The most recent command is always kept even if it exceeds [`MaxUndos::MAX_PIXELS`].
+    pub fn forget_exceeded_commands(&mut self, config: &ConfigModel) {
+        let max_commands = config.max_undos.get() as usize;
+        while self.command_log_tail > max_commands
+            || (self.command_log_tail > 1 && self.command_log_pixels > MaxUndos::MAX_PIXELS)
+        {
+            self.forget_oldest_command();
+        }
+    }
+
     pub fn region(&self) -> PixelRegion {
         PixelRegion::from_positions(self.pixels.pixels.keys().copied())
     }
@@ -154,9 +293,10 @@ impl PixelCanvasModel {
         // TODO: optimize (e.g., use cache to avoid redundant calculation)
         let frame = config.frame;
         let layer = config.layer;
+        let layer_attrs = config.layer_attrs;
         let frame_count = config.animation.enabled_frame_count();
         region.pixels().filter_map(move |position| {
-            self.get_layered_pixel(frame, frame_count, layer, position)
+            self.get_layered_pixel(frame, frame_count, layer, layer_attrs, position)
                 .map(|color| Pixel::new(position, color))
         })
     }
@@ -166,6 +306,7 @@ impl PixelCanvasModel {
             config.frame,
             config.animation.enabled_frame_count(),
             config.layer,
+            config.layer_attrs,
             position,
         )
     }
@@ -177,12 +318,14 @@ impl PixelCanvasModel {
         alpha: u8,
     ) -> Option<Rgba> {
         let layer = config.layer;
+        let layer_attrs = config.layer_attrs;
         let frame = config.frame;
         let frame_count = config.animation.enabled_frame_count();
         let mut color = None;
         layer.for_each_lower_layer_pixel_but_last(frame, frame_count, position, |position| {
-            if let Some(c) = self.pixels.get_pixel(position) {
-                color = Some(color.map_or(c, |d| c.alpha_blend(d)));
+            let attrs = layer_attrs.get_by_position(layer, frame, frame_count, position);
+            if let Some(c) = self.pixels.get_pixel(position).and_then(|c| attrs.apply(c)) {
+                color = Some(color.map_or(c, |d| attrs.blend_mode.blend(c, d)));
             }
         });
 
@@ -190,14 +333,29 @@ impl PixelCanvasModel {
             return color;
         }
 
-        if let Some(mut c) = self.get_direct_pixel(position) {
+        let attrs = layer_attrs.get_by_position(layer, frame, frame_count, position);
+        if let Some(mut c) = self.get_direct_pixel(position).and_then(|c| attrs.apply(c)) {
             c.a = alpha;
-            Some(color.map_or(c, |d| c.alpha_blend(d)))
+            Some(color.map_or(c, |d| attrs.blend_mode.blend(c, d)))
         } else {
             color
         }
     }
 
+    /// Returns the colors used in the canvas (in arbitrary order).
+    pub fn colors(&self) -> impl '_ + Iterator<Item = Rgba> {
+        self.pixels.color_index.keys().copied()
+    }
+
+    /// Returns the positions of the pixels having the given color (in ascending order).
+    pub fn color_positions(&self, color: Rgba) -> impl '_ + Iterator<Item = PixelPosition> {
+        self.pixels
+            .color_index
+            .get(&color)
+            .into_iter()
+            .flat_map(|positions| positions.iter().copied())
+    }
+
     pub fn get_direct_pixel(&self, position: PixelPosition) -> Option<Rgba> {
         self.pixels.get_pixel(position)
     }
@@ -211,18 +369,20 @@ impl PixelCanvasModel {
         frame: FrameRegion,
         frame_count: u16,
         layer: Layer,
+        layer_attrs: LayerAttributesTable,
         position: PixelPosition,
     ) -> Option<Rgba> {
         let mut color = None;
         layer.for_each_lower_layer_pixel(frame, frame_count, position, |position| {
-            if let Some(c) = self.pixels.get_pixel(position) {
-                color = Some(color.map_or(c, |d| c.alpha_blend(d)));
+            let attrs = layer_attrs.get_by_position(layer, frame, frame_count, position);
+            if let Some(c) = self.pixels.get_pixel(position).and_then(|c| attrs.apply(c)) {
+                color = Some(color.map_or(c, |d| attrs.blend_mode.blend(c, d)));
             }
         });
         color
     }
 
-    pub fn undo_command(&mut self, config: &ConfigModel) -> Result<()> {
+    pub fn undo_command(&mut self, config: &mut ConfigModel) -> Result<()> {
         if let Some(i) = self.command_log_tail.checked_sub(1) {
             let layer = config.layer;
             let frame = config.frame;
@@ -240,13 +400,29 @@ impl PixelCanvasModel {
                     self.dirty_positions.insert(position);
                 });
             }
+            if let Some((before, _)) = command.band_config.as_deref() {
+                config.set_band_config(before);
+            }
             self.command_log_tail = i;
         }
         self.state_version -= 1;
         Ok(())
     }
 
-    pub fn redo_command(&mut self, config: &ConfigModel) -> Result<()> {
+    pub fn redo_command(&mut self, config: &mut ConfigModel) -> Result<()> {
+        let band_config = self
+            .command_log
+            .get(self.command_log_tail)
+            .and_then(|command| command.band_config.as_deref())
+            .map(|(_, after)| after.clone());
+        self.redo_pixels(config).or_fail()?;
+        if let Some(band_config) = band_config {
+            config.set_band_config(&band_config);
+        }
+        Ok(())
+    }
+
+    fn redo_pixels(&mut self, config: &ConfigModel) -> Result<()> {
         if let Some(command) = self.command_log.get(self.command_log_tail) {
             let layer = config.layer;
             let frame = config.frame;
@@ -271,7 +447,9 @@ impl PixelCanvasModel {
 
     pub fn forget_oldest_command(&mut self) {
         if self.command_log_tail > 0 {
-            self.command_log.pop_front();
+            if let Some(command) = self.command_log.pop_front() {
+                self.command_log_pixels -= command.pixel_count();
+            }
             self.command_log_tail -= 1;
         }
     }
@@ -291,15 +469,24 @@ impl PixelCanvasModel {
     pub fn dirty_positions(&self) -> &BTreeSet<PixelPosition> {
         &self.dirty_positions
     }
-}
 
-impl Serialize for PixelCanvasModel {
-    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+    /// Serializes this model along with (at most) the last `max_commands` undoable commands.
+    ///
+    /// Redoable commands are not serialized.
+    pub fn serialize_with_history<W: Write>(
+        &self,
+        writer: &mut W,
+        max_commands: usize,
+    ) -> Result<()> {
         let mut writer = libflate::deflate::Encoder::new(writer);
 
-        // Don't serializing the command log
-        0usize.serialize(&mut writer).or_fail()?;
-        0usize.serialize(&mut writer).or_fail()?;
+        let start = self.command_log_tail.saturating_sub(max_commands);
+        let commands = self.command_log.range(start..self.command_log_tail);
+        commands.len().serialize(&mut writer).or_fail()?;
+        for command in commands.clone() {
+            command.serialize(&mut writer).or_fail()?;
+        }
+        commands.len().serialize(&mut writer).or_fail()?;
 
         self.pixels.serialize(&mut writer).or_fail()?;
         writer.finish().into_result().or_fail()?;
@@ -307,13 +494,24 @@ impl Serialize for PixelCanvasModel {
     }
 }
 
+impl Serialize for PixelCanvasModel {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        // Don't serializing the command log
+        self.serialize_with_history(writer, 0).or_fail()
+    }
+}
+
 impl Deserialize for PixelCanvasModel {
     fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
         let mut reader = libflate::deflate::Decoder::new(reader);
+        let command_log: VecDeque<PixelCanvasCommand> =
+            Deserialize::deserialize(&mut reader).or_fail()?;
+        let command_log_pixels = command_log.iter().map(|c| c.pixel_count()).sum();
         Ok(Self {
-            command_log: Deserialize::deserialize(&mut reader).or_fail()?,
+            command_log,
             command_log_tail: Deserialize::deserialize(&mut reader).or_fail()?,
             pixels: Deserialize::deserialize(&mut reader).or_fail()?,
+            command_log_pixels,
             dirty_positions: Default::default(),
             state_version: 0,
         })
@@ -324,12 +522,19 @@ impl Deserialize for PixelCanvasModel {
 pub struct PixelCanvasCommand {
     pub erase: Vec<Pixel>,
     pub draw: Vec<Pixel>,
+
+    // The band config before and after a layer or frame edit.
+    pub band_config: Option<Box<(BandConfig, BandConfig)>>,
 }
 
 impl PixelCanvasCommand {
     fn new() -> Self {
         Self::default()
     }
+
+    pub fn pixel_count(&self) -> usize {
+        self.erase.len() + self.draw.len()
+    }
 }
 
 impl Serialize for PixelCanvasCommand {
@@ -346,6 +551,11 @@ impl Serialize for PixelCanvasCommand {
         for pixel in self.erase.iter().chain(self.draw.iter()) {
             pixel.color.serialize(writer).or_fail()?;
         }
+        self.band_config.is_some().serialize(writer).or_fail()?;
+        if let Some((before, after)) = self.band_config.as_deref() {
+            before.serialize(writer).or_fail()?;
+            after.serialize(writer).or_fail()?;
+        }
         Ok(())
     }
 }
@@ -368,7 +578,18 @@ impl Deserialize for PixelCanvasCommand {
             .copied()
             .map(|pos| Ok(Pixel::new(pos, Rgba::deserialize(reader).or_fail()?)))
             .collect::<Result<Vec<_>>>()?;
-        Ok(Self { erase, draw })
+        let band_config = if bool::deserialize(reader).or_fail()? {
+            let before = BandConfig::deserialize(reader).or_fail()?;
+            let after = BandConfig::deserialize(reader).or_fail()?;
+            Some(Box::new((before, after)))
+        } else {
+            None
+        };
+        Ok(Self {
+            erase,
+            draw,
+            band_config,
+        })
     }
 }
 
@@ -378,6 +599,9 @@ struct Pixels {
     non_neg_right_bottom: PixelPosition,
     non_neg_ys: BTreeMap<i16, usize>,
     non_neg_xs: BTreeMap<i16, usize>,
+
+    // Positions of each color (not serialized).
+    color_index: HashMap<Rgba, BTreeSet<PixelPosition>>,
 }
 
 impl Pixels {
@@ -393,6 +617,12 @@ impl Pixels {
         };
         prev.is_none().or_fail()?;
 
+        if pixel.color.a != 0 {
+            self.color_index
+                .entry(pixel.color)
+                .or_default()
+                .insert(pixel.position);
+        }
         if pixel.position.is_non_negative() {
             self.on_non_neg_pixel_drawn(pixel.position);
         }
@@ -404,6 +634,12 @@ impl Pixels {
         let prev = self.pixels.remove(&pixel.position);
         (prev == Some(pixel.color)).or_fail()?;
 
+        if let Some(positions) = self.color_index.get_mut(&pixel.color) {
+            positions.remove(&pixel.position);
+            if positions.is_empty() {
+                self.color_index.remove(&pixel.color);
+            }
+        }
         if pixel.position.is_non_negative() {
             self.on_non_neg_pixel_erased(pixel.position);
         }
@@ -471,7 +707,8 @@ impl Deserialize for Pixels {
             .collect::<Result<BTreeMap<_, _>>>()?;
 
         let mut this = Self::default();
-        for position in pixels.keys().copied() {
+        for (&position, &color) in &pixels {
+            this.color_index.entry(color).or_default().insert(position);
             if position.is_non_negative() {
                 this.on_non_neg_pixel_drawn(position);
             }
@@ -523,3 +760,260 @@ fn deserialize_positions<R: Read>(
 
     Ok(positions.into_iter())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_with_history_works() {
+        let mut config = ConfigModel::default();
+        let mut canvas = PixelCanvasModel::default();
+        for i in 0..3 {
+            let pixel = Pixel::new(PixelPosition::from_xy(i, i), Rgba::new(255, 0, 0, 255));
+            canvas
+                .draw_pixels(&config, std::iter::once(pixel))
+                .expect("draw");
+        }
+        canvas.undo_command(&mut config).expect("undo");
+
+        let mut buf = Vec::new();
+        canvas
+            .serialize_with_history(&mut buf, 1)
+            .expect("serialize");
+        let mut canvas = PixelCanvasModel::deserialize(&mut &buf[..]).expect("deserialize");
+        assert_eq!(canvas.command_log().len(), 1);
+        assert_eq!(canvas.command_log_tail(), 1);
+        assert_eq!(canvas.raw_pixels().count(), 2);
+
+        canvas.undo_command(&mut config).expect("undo");
+        assert_eq!(canvas.raw_pixels().count(), 1);
+        assert_eq!(canvas.command_log_tail(), 0);
+    }
+
+    #[test]
+    fn replace_color_works() {
+        let mut config = ConfigModel::default();
+        let red = Rgba::new(255, 0, 0, 255);
+        let blue = Rgba::new(0, 0, 255, 255);
+
+        let mut canvas = PixelCanvasModel::default();
+        let pixels = (0..4).map(|i| {
+            let color = if i % 2 == 0 { red } else { blue };
+            Pixel::new(PixelPosition::from_xy(i, 0), color)
+        });
+        canvas.draw_pixels(&config, pixels).expect("draw");
+
+        canvas.replace_color(&config, red, blue).expect("replace");
+        assert_eq!(canvas.color_positions(red).count(), 0);
+        assert_eq!(canvas.color_positions(blue).count(), 4);
+
+        canvas.undo_command(&mut config).expect("undo");
+        assert_eq!(
+            canvas.color_positions(red).collect::<Vec<_>>(),
+            [PixelPosition::from_xy(0, 0), PixelPosition::from_xy(2, 0)]
+        );
+    }
+
+    #[test]
+    fn erase_and_draw_pixels_with_locked_layer() {
+        let mut config = ConfigModel::default();
+        config.layer.set_enabled(true);
+        config.layer.set_count(2);
+        config.layer_attrs.get_mut(1).expect("layer").locked = true;
+        let height = config.frame.get_base_region().size().height as i16;
+
+        let mut canvas = PixelCanvasModel::default();
+        let unlocked = PixelPosition::from_xy(0, 0);
+        let locked = PixelPosition::from_xy(0, height);
+        let color = Rgba::new(255, 0, 0, 255);
+        canvas
+            .draw_pixels(&config, std::iter::once(Pixel::new(unlocked, color)))
+            .expect("draw");
+
+        // Moving a pixel into a locked layer doesn't delete it.
+        let moved = canvas
+            .erase_and_draw_pixels(
+                &config,
+                std::iter::once(unlocked),
+                std::iter::once(Pixel::new(locked, color)),
+            )
+            .expect("move");
+        assert!(!moved);
+        assert_eq!(canvas.get_direct_pixel(unlocked), Some(color));
+        assert_eq!(canvas.get_direct_pixel(locked), None);
+        assert_eq!(canvas.command_log_tail(), 1);
+    }
+
+    #[test]
+    fn edit_layers_works() {
+        let mut config = ConfigModel::default();
+        config.layer.set_enabled(true);
+        config.layer.set_count(3);
+        let height = config.frame.get_base_region().size().height as i16;
+
+        let mut canvas = PixelCanvasModel::default();
+        let pixels = (0..3).map(|i| {
+            let position = PixelPosition::from_xy(0, i * height);
+            Pixel::new(position, Rgba::new(i as u8, 0, 0, 255))
+        });
+        canvas.draw_pixels(&config, pixels).expect("draw");
+        let colors = |canvas: &PixelCanvasModel| {
+            (0..4)
+                .map(|i| {
+                    canvas
+                        .get_direct_pixel(PixelPosition::from_xy(0, i * height))
+                        .map(|c| c.r)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        config.layer_attrs.get_mut(2).expect("layer").locked = true;
+        canvas
+            .edit_layers(&mut config, BandEdit::Swap(0, 2))
+            .expect("swap");
+        assert_eq!(colors(&canvas), [Some(2), Some(1), Some(0), None]);
+        assert!(config.layer_attrs.get(0).locked);
+
+        canvas
+            .edit_layers(&mut config, BandEdit::Delete(1))
+            .expect("delete");
+        assert_eq!(colors(&canvas), [Some(2), Some(0), None, None]);
+        assert_eq!(config.layer.count(), 2);
+
+        canvas
+            .edit_layers(&mut config, BandEdit::Duplicate(0))
+            .expect("duplicate");
+        assert_eq!(colors(&canvas), [Some(2), Some(2), Some(0), None]);
+
+        canvas
+            .edit_layers(&mut config, BandEdit::Insert(0))
+            .expect("insert");
+        assert_eq!(colors(&canvas), [None, Some(2), Some(2), Some(0)]);
+        assert_eq!(config.layer.count(), 4);
+        assert!(config.layer_attrs.get(1).locked);
+
+        // Each edit is a single undo step (including the layer count and attributes).
+        canvas.undo_command(&mut config).expect("undo");
+        canvas.undo_command(&mut config).expect("undo");
+        assert_eq!(colors(&canvas), [Some(2), Some(0), None, None]);
+        assert_eq!(config.layer.count(), 2);
+        assert!(config.layer_attrs.get(0).locked);
+
+        canvas.redo_command(&mut config).expect("redo");
+        assert_eq!(config.layer.count(), 3);
+
+        assert!(
+            canvas
+                .edit_layers(&mut config, BandEdit::Swap(0, 3))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn edit_layers_overwrites_pixels_beyond_layers() {
+        let mut config = ConfigModel::default();
+        config.layer.set_enabled(true);
+        config.layer.set_count(2);
+        let height = config.frame.get_base_region().size().height as i16;
+
+        let mut canvas = PixelCanvasModel::default();
+        let pixels = [1, 2].map(|i| {
+            let position = PixelPosition::from_xy(0, i * height);
+            Pixel::new(position, Rgba::new(i as u8, 0, 0, 255))
+        });
+        canvas
+            .draw_pixels(&config, pixels.into_iter())
+            .expect("draw");
+
+        canvas
+            .edit_layers(&mut config, BandEdit::Insert(0))
+            .expect("insert");
+        assert_eq!(canvas.raw_pixels().count(), 1);
+        assert_eq!(
+            canvas.get_direct_pixel(PixelPosition::from_xy(0, 2 * height)),
+            Some(Rgba::new(1, 0, 0, 255))
+        );
+
+        canvas.undo_command(&mut config).expect("undo");
+        assert_eq!(canvas.raw_pixels().count(), 2);
+        assert_eq!(
+            canvas.get_direct_pixel(PixelPosition::from_xy(0, 2 * height)),
+            Some(Rgba::new(2, 0, 0, 255))
+        );
+    }
+
+    #[test]
+    fn edit_frames_works() {
+        let mut config = ConfigModel::default();
+        config.animation.set_enabled(true);
+        config.animation.set_frame_count(3);
+        let width = config.frame.get_base_region().size().width as i16;
+
+        let mut canvas = PixelCanvasModel::default();
+        let pixels = (0..3).map(|i| {
+            let position = PixelPosition::from_xy(i * width, 0);
+            Pixel::new(position, Rgba::new(i as u8, 0, 0, 255))
+        });
+        canvas.draw_pixels(&config, pixels).expect("draw");
+        let colors = |canvas: &PixelCanvasModel| {
+            (0..3)
+                .map(|i| {
+                    canvas
+                        .get_direct_pixel(PixelPosition::from_xy(i * width, 0))
+                        .map(|c| c.r)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        config.frame_durations.set_millis(0, Some(500));
+        canvas
+            .edit_frames(&mut config, BandEdit::Move(0, 2))
+            .expect("move");
+        assert_eq!(colors(&canvas), [Some(1), Some(2), Some(0)]);
+        assert_eq!(config.frame_durations.get_millis(2), Some(500));
+
+        canvas
+            .edit_frames(&mut config, BandEdit::Delete(2))
+            .expect("delete");
+        assert_eq!(colors(&canvas), [Some(1), Some(2), None]);
+        assert_eq!(config.animation.frame_count(), 2);
+        assert_eq!(config.frame_durations.get_millis(2), None);
+
+        // Each edit is a single undo step (including the frame count and durations).
+        canvas.undo_command(&mut config).expect("undo");
+        assert_eq!(config.animation.frame_count(), 3);
+        assert_eq!(config.frame_durations.get_millis(2), Some(500));
+        assert_eq!(colors(&canvas), [Some(1), Some(2), Some(0)]);
+    }
+
+    #[test]
+    fn edit_frames_overwrites_pixels_beyond_frames() {
+        let mut config = ConfigModel::default();
+        config.animation.set_enabled(true);
+        config.animation.set_frame_count(2);
+        let width = config.frame.get_base_region().size().width as i16;
+
+        let mut canvas = PixelCanvasModel::default();
+        let pixels = [1, 2].map(|i| {
+            let position = PixelPosition::from_xy(i * width, 0);
+            Pixel::new(position, Rgba::new(i as u8, 0, 0, 255))
+        });
+        canvas
+            .draw_pixels(&config, pixels.into_iter())
+            .expect("draw");
+
+        canvas
+            .edit_frames(&mut config, BandEdit::Duplicate(1))
+            .expect("duplicate");
+        assert_eq!(canvas.raw_pixels().count(), 2);
+        assert_eq!(config.animation.frame_count(), 3);
+
+        canvas.undo_command(&mut config).expect("undo");
+        assert_eq!(
+            canvas.get_direct_pixel(PixelPosition::from_xy(2 * width, 0)),
+            Some(Rgba::new(2, 0, 0, 255))
+        );
+        assert_eq!(config.animation.frame_count(), 2);
+    }
+}
@@ -18,6 +18,26 @@ impl ToolModel {
         self.current
     }
 
+    pub fn tool_icon(&self, kind: ToolKind) -> IconId {
+        match kind {
+            ToolKind::Draw => self.draw.icon(),
+            ToolKind::Fill => IconId::Bucket,
+            ToolKind::Erase => self.erase.icon(),
+            ToolKind::Select => self.select.icon(),
+            ToolKind::Move => IconId::Move,
+            ToolKind::Pick => IconId::Pick,
+        }
+    }
+
+    pub fn switch_sub_tool(&mut self) {
+        match self.current {
+            ToolKind::Draw => self.draw = self.draw.next(),
+            ToolKind::Erase => self.erase = self.erase.next(),
+            ToolKind::Select => self.select = self.select.next(),
+            _ => {}
+        }
+    }
+
     pub fn marker_kind(&self) -> MarkerKind {
         match self.current {
             ToolKind::Draw => self.draw.marker(),
@@ -34,9 +54,9 @@ impl Default for ToolModel {
     fn default() -> Self {
         Self {
             current: ToolKind::Draw,
-            draw: DrawTool,
-            erase: EraseTool,
-            select: SelectTool,
+            draw: DrawTool::default(),
+            erase: EraseTool::default(),
+            select: SelectTool::default(),
             r#move: MoveToolState {
                 marker: MarkerKind::Noop,
             },
@@ -85,41 +105,112 @@ impl ToolKind {
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-pub struct DrawTool;
+pub enum DrawTool {
+    #[default]
+    Stroke,
+    Line,
+    Rectangle,
+    FilledRectangle,
+    Ellipse,
+}
 
 impl DrawTool {
     fn marker(self) -> MarkerKind {
-        MarkerKind::Stroke
+        match self {
+            Self::Stroke => MarkerKind::Stroke,
+            Self::Line => MarkerKind::Line,
+            Self::Rectangle => MarkerKind::Rectangle,
+            Self::FilledRectangle => MarkerKind::FilledRectangle,
+            Self::Ellipse => MarkerKind::Ellipse,
+        }
     }
 
     pub fn icon(self) -> IconId {
-        IconId::PenStroke
+        match self {
+            Self::Stroke => IconId::PenStroke,
+            Self::Line => IconId::PenLine,
+            Self::Rectangle => IconId::PenRectangle,
+            Self::FilledRectangle => IconId::PenFilledRectangle,
+            Self::Ellipse => IconId::PenCircle,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Stroke => Self::Line,
+            Self::Line => Self::Rectangle,
+            Self::Rectangle => Self::FilledRectangle,
+            Self::FilledRectangle => Self::Ellipse,
+            Self::Ellipse => Self::Stroke,
+        }
     }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-pub struct EraseTool;
+pub enum EraseTool {
+    #[default]
+    Stroke,
+    Rectangle,
+    Lasso,
+}
 
 impl EraseTool {
     fn marker(self) -> MarkerKind {
-        MarkerKind::Stroke
+        match self {
+            Self::Stroke => MarkerKind::Stroke,
+            Self::Rectangle => MarkerKind::FilledRectangle,
+            Self::Lasso => MarkerKind::Lasso,
+        }
     }
 
     pub fn icon(self) -> IconId {
-        IconId::Erase
+        match self {
+            Self::Stroke => IconId::Erase,
+            Self::Rectangle => IconId::ScissorRectangle,
+            Self::Lasso => IconId::ScissorLasso,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Stroke => Self::Rectangle,
+            Self::Rectangle => Self::Lasso,
+            Self::Lasso => Self::Stroke,
+        }
     }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-pub struct SelectTool;
+pub enum SelectTool {
+    #[default]
+    Lasso,
+    Rectangle,
+    Bucket,
+}
 
 impl SelectTool {
     fn marker(self) -> MarkerKind {
-        MarkerKind::Lasso
+        match self {
+            Self::Lasso => MarkerKind::Lasso,
+            Self::Rectangle => MarkerKind::FilledRectangle,
+            Self::Bucket => MarkerKind::Fill,
+        }
     }
 
     pub fn icon(self) -> IconId {
-        IconId::Lasso
+        match self {
+            Self::Lasso => IconId::Lasso,
+            Self::Rectangle => IconId::Select,
+            Self::Bucket => IconId::SelectBucket,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Lasso => Self::Rectangle,
+            Self::Rectangle => Self::Bucket,
+            Self::Bucket => Self::Lasso,
+        }
     }
 }
 
@@ -1,11 +1,202 @@
 use orfail::{OrFail, Result};
-use pagurus::{image::Sprite, spatial::Size};
+use pagurus::{
+    image::{Rgba, Sprite},
+    spatial::{Contains, Position, Region, Size},
+};
+use png::{BlendOp, DisposeOp};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+#[derive(Debug)]
+pub struct AnimationFrame {
+    pub sprite: Sprite,
+    pub delay: Duration,
+}
 
 pub fn decode_sprite(png: &[u8]) -> Result<Sprite> {
-    let decoder = png::Decoder::new(png);
+    let decoder = new_decoder(png);
     let mut reader = decoder.read_info().or_fail()?;
     let mut buf = vec![0; reader.output_buffer_size()];
     let info = reader.next_frame(&mut buf).or_fail()?;
+    to_sprite(&info, &buf).or_fail()
+}
+
+// Indexed and sub-8-bit images are expanded to 8-bit ones (with tRNS applied),
+// and 16-bit images are downsampled to 8-bit ones.
+fn new_decoder(png: &[u8]) -> png::Decoder<&[u8]> {
+    let mut decoder = png::Decoder::new(png);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    decoder
+}
+
+/// Decodes all the frames of an (A)PNG image.
+///
+/// Each returned frame is fully composed (i.e., the blend and dispose operations have been applied)
+/// and has the same size as the image.
+/// If the image is not animated, a single frame is returned.
+pub fn decode_animation(png: &[u8]) -> Result<Vec<AnimationFrame>> {
+    let decoder = new_decoder(png);
+    let mut reader = decoder.read_info().or_fail()?;
+    let Some(animation) = reader.info().animation_control else {
+        let sprite = decode_sprite(png).or_fail()?;
+        let delay = Duration::ZERO;
+        return Ok(vec![AnimationFrame { sprite, delay }]);
+    };
+
+    let (width, height) = reader.info().size();
+    let size = Size::from_wh(width, height);
+    let mut buf = vec![0; reader.output_buffer_size()];
+    if reader.info().frame_control.is_none() {
+        // The default image is not a part of the animation.
+        reader.next_frame(&mut buf).or_fail()?;
+    }
+
+    let mut canvas = vec![Rgba::new(0, 0, 0, 0); size.len()];
+    // Don't trust `num_frames` to reserve the capacity as it comes from the file.
+    let mut frames = Vec::new();
+    for _ in 0..animation.num_frames {
+        let info = reader.next_frame(&mut buf).or_fail()?;
+        let control = reader.info().frame_control.or_fail()?;
+        let subframe = to_sprite(&info, &buf).or_fail()?;
+        let region = Region::new(
+            Position::from_xy(control.x_offset as i32, control.y_offset as i32),
+            subframe.size(),
+        );
+        size.to_region().contains(&region).or_fail()?;
+
+        let previous = (control.dispose_op == DisposeOp::Previous).then(|| canvas.clone());
+        for (position, color) in subframe.pixels() {
+            let position = position + region.position;
+            let i = position.y as usize * width as usize + position.x as usize;
+            canvas[i] = match control.blend_op {
+                BlendOp::Source => color,
+                BlendOp::Over => color.alpha_blend(canvas[i]),
+            };
+        }
+
+        let bytes = canvas
+            .iter()
+            .flat_map(|c| [c.r, c.g, c.b, c.a])
+            .collect::<Vec<_>>();
+        let sprite = Sprite::from_rgba32_bytes(&bytes, size).or_fail()?;
+        let delay_den = if control.delay_den == 0 {
+            100
+        } else {
+            control.delay_den
+        };
+        let delay = Duration::from_secs(u64::from(control.delay_num)) / u32::from(delay_den);
+        frames.push(AnimationFrame { sprite, delay });
+
+        match control.dispose_op {
+            DisposeOp::None => {}
+            DisposeOp::Background => {
+                for position in region.iter() {
+                    let i = position.y as usize * width as usize + position.x as usize;
+                    canvas[i] = Rgba::new(0, 0, 0, 0);
+                }
+            }
+            DisposeOp::Previous => {
+                canvas = previous.or_fail()?;
+            }
+        }
+    }
+    Ok(frames)
+}
+
+/// RGBA32 frames converted into palette indices (see [`to_indexed_frames()`]).
+#[derive(Debug)]
+pub struct IndexedFrames {
+    /// RGB entries for the PLTE chunk.
+    pub palette: Vec<u8>,
+
+    /// Alpha entries for the tRNS chunk (empty if all the colors are opaque).
+    pub trns: Vec<u8>,
+
+    pub bit_depth: png::BitDepth,
+
+    /// Packed index data of each frame.
+    pub frames: Vec<Vec<u8>>,
+}
+
+/// Returns whether [`to_indexed_frames()`] can convert the given RGBA32 frames (i.e., they have 256 colors or less).
+pub fn is_indexable(frames: &[Vec<u8>]) -> bool {
+    let mut seen = HashSet::new();
+    frames
+        .iter()
+        .flat_map(|frame| frame.chunks_exact(4))
+        .all(|rgba| {
+            seen.insert(rgba);
+            seen.len() <= 256
+        })
+}
+
+/// Converts RGBA32 frames into indexed ones if they have 256 colors or less.
+pub fn to_indexed_frames(frames: &[Vec<u8>], width: usize) -> Option<IndexedFrames> {
+    let mut colors = Vec::new();
+    let mut seen = HashMap::new();
+    for rgba in frames.iter().flat_map(|frame| frame.chunks_exact(4)) {
+        if !seen.contains_key(rgba) {
+            if seen.len() == 256 {
+                return None;
+            }
+            seen.insert(rgba, 0);
+            colors.push(rgba);
+        }
+    }
+
+    // Place non-opaque colors first so that the tRNS chunk can be as short as possible.
+    colors.sort_by_key(|rgba| rgba[3] == u8::MAX);
+    for (i, rgba) in colors.iter().enumerate() {
+        seen.insert(rgba, i as u8);
+    }
+    let palette = colors.iter().flat_map(|rgba| &rgba[..3]).copied().collect();
+    let trns = colors
+        .iter()
+        .map(|rgba| rgba[3])
+        .take_while(|&a| a != u8::MAX)
+        .collect();
+
+    let (bit_depth, bits) = match colors.len() {
+        0..=2 => (png::BitDepth::One, 1),
+        3..=4 => (png::BitDepth::Two, 2),
+        5..=16 => (png::BitDepth::Four, 4),
+        _ => (png::BitDepth::Eight, 8),
+    };
+    let frames = frames
+        .iter()
+        .map(|frame| {
+            let mut packed = Vec::new();
+            for row in frame.chunks_exact(width * 4) {
+                let mut byte = 0;
+                let mut offset = 0;
+                for rgba in row.chunks_exact(4) {
+                    byte |= seen[rgba] << (8 - bits - offset);
+                    offset += bits;
+                    if offset == 8 {
+                        packed.push(byte);
+                        byte = 0;
+                        offset = 0;
+                    }
+                }
+                if offset != 0 {
+                    packed.push(byte);
+                }
+            }
+            packed
+        })
+        .collect();
+
+    Some(IndexedFrames {
+        palette,
+        trns,
+        bit_depth,
+        frames,
+    })
+}
+
+fn to_sprite(info: &png::OutputInfo, buf: &[u8]) -> Result<Sprite> {
     let bytes = &buf[..info.buffer_size()];
     let size = Size::from_wh(info.width, info.height);
     (info.bit_depth == png::BitDepth::Eight)
@@ -18,15 +209,191 @@ pub fn decode_sprite(png: &[u8]) -> Result<Sprite> {
         png::ColorType::GrayscaleAlpha => {
             Sprite::from_grayscale_alpha16_bytes(bytes, size).or_fail()
         }
-        png::ColorType::Indexed => {
-            let palette = reader.info().palette.as_ref().or_fail()?;
-            let mut rgb_bytes = Vec::with_capacity(size.len());
-            for i in bytes.iter().copied().map(usize::from) {
-                rgb_bytes.push(palette[i * 3]);
-                rgb_bytes.push(palette[i * 3 + 1]);
-                rgb_bytes.push(palette[i * 3 + 2]);
-            }
-            Sprite::from_rgb24_bytes(&rgb_bytes, size).or_fail()
+        png::ColorType::Indexed => Err(orfail::Failure::new(
+            "unreachable: indexed PNG images should have been expanded",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_png(
+        size: (u32, u32),
+        color: png::ColorType,
+        depth: png::BitDepth,
+        palette: &[u8],
+        trns: &[u8],
+        frames: &[&[u8]],
+    ) -> Vec<u8> {
+        let mut png_data = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut png_data, size.0, size.1);
+            encoder.set_color(color);
+            encoder.set_depth(depth);
+            if !palette.is_empty() {
+                encoder.set_palette(palette.to_vec());
+            }
+            if !trns.is_empty() {
+                encoder.set_trns(trns.to_vec());
+            }
+            if frames.len() > 1 {
+                encoder
+                    .set_animated(frames.len() as u32, 0)
+                    .expect("set_animated");
+            }
+            let mut writer = encoder.write_header().expect("write_header");
+            for (i, frame) in frames.iter().enumerate() {
+                if frames.len() > 1 {
+                    writer
+                        .set_frame_delay(i as u16 + 1, 10)
+                        .expect("set_frame_delay");
+                }
+                writer.write_image_data(frame).expect("write_image_data");
+            }
         }
+        png_data
+    }
+
+    fn colors(sprite: &Sprite) -> Vec<Rgba> {
+        sprite.pixels().map(|(_, color)| color).collect()
+    }
+
+    #[test]
+    fn decode_16bit_rgba_works() {
+        let data = [
+            0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xff, 0xff, // Opaque.
+            0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x80, 0x01, // Semi-transparent.
+        ];
+        let png_data = encode_png(
+            (2, 1),
+            png::ColorType::Rgba,
+            png::BitDepth::Sixteen,
+            &[],
+            &[],
+            &[&data],
+        );
+
+        let sprite = decode_sprite(&png_data).expect("decode");
+        assert_eq!(
+            colors(&sprite),
+            [
+                Rgba::new(0x12, 0x56, 0x9a, 0xff),
+                Rgba::new(0, 0xff, 0, 0x80)
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_indexed_with_trns_works() {
+        let palette = [255, 0, 0, 0, 255, 0, 0, 0, 255];
+        let trns = [0, 128];
+        let data = [0b00_01_10_00];
+        let png_data = encode_png(
+            (3, 1),
+            png::ColorType::Indexed,
+            png::BitDepth::Two,
+            &palette,
+            &trns,
+            &[&data],
+        );
+
+        let sprite = decode_sprite(&png_data).expect("decode");
+        assert_eq!(
+            colors(&sprite),
+            [
+                Rgba::new(255, 0, 0, 0),
+                Rgba::new(0, 255, 0, 128),
+                Rgba::new(0, 0, 255, 255),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_grayscale_alpha_works() {
+        let data = [100, 255, 200, 50];
+        let png_data = encode_png(
+            (2, 1),
+            png::ColorType::GrayscaleAlpha,
+            png::BitDepth::Eight,
+            &[],
+            &[],
+            &[&data],
+        );
+
+        let sprite = decode_sprite(&png_data).expect("decode");
+        assert_eq!(
+            colors(&sprite),
+            [Rgba::new(100, 100, 100, 255), Rgba::new(200, 200, 200, 50)]
+        );
+    }
+
+    #[test]
+    fn to_indexed_frames_round_trip_works() {
+        let red = [255, 0, 0, 255];
+        let clear = [0, 0, 0, 0];
+        let blue = [0, 0, 255, 128];
+        let frame = [red, clear, blue, red, red, blue].concat();
+
+        let indexed = to_indexed_frames(std::slice::from_ref(&frame), 3).expect("indexed");
+        assert_eq!(indexed.bit_depth, png::BitDepth::Two);
+        assert_eq!(indexed.trns.len(), 2);
+        let png_data = encode_png(
+            (3, 2),
+            png::ColorType::Indexed,
+            indexed.bit_depth,
+            &indexed.palette,
+            &indexed.trns,
+            &[&indexed.frames[0]],
+        );
+
+        let sprite = decode_sprite(&png_data).expect("decode");
+        let bytes = colors(&sprite)
+            .into_iter()
+            .flat_map(|c| [c.r, c.g, c.b, c.a])
+            .collect::<Vec<_>>();
+        assert_eq!(bytes, frame);
+    }
+
+    #[test]
+    fn is_indexable_works() {
+        let frame = |colors: u32| {
+            (0..colors)
+                .flat_map(|i| [i as u8, (i >> 8) as u8, 0, 255])
+                .collect::<Vec<_>>()
+        };
+        assert!(is_indexable(&[frame(256), frame(256)]));
+        assert!(!is_indexable(&[frame(257)]));
+        assert_eq!(
+            is_indexable(&[frame(257)]),
+            to_indexed_frames(&[frame(257)], 257).is_some()
+        );
+    }
+
+    #[test]
+    fn decode_animation_works() {
+        let frames = [[1, 2, 3, 255, 0, 0, 0, 0], [0, 0, 0, 0, 4, 5, 6, 255]];
+        let png_data = encode_png(
+            (2, 1),
+            png::ColorType::Rgba,
+            png::BitDepth::Eight,
+            &[],
+            &[],
+            &[&frames[0], &frames[1]],
+        );
+
+        let decoded = decode_animation(&png_data).expect("decode");
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(
+            colors(&decoded[0].sprite),
+            [Rgba::new(1, 2, 3, 255), Rgba::new(0, 0, 0, 0)]
+        );
+        assert_eq!(
+            colors(&decoded[1].sprite),
+            [Rgba::new(0, 0, 0, 0), Rgba::new(4, 5, 6, 255)]
+        );
+        assert_eq!(decoded[0].delay, Duration::from_millis(100));
+        assert_eq!(decoded[1].delay, Duration::from_millis(200));
     }
 }
@@ -224,6 +224,42 @@ impl<T: Deserialize> Deserialize for VecDeque<T> {
     }
 }
 
+impl<T: Serialize> Serialize for Vec<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.len().serialize(writer).or_fail()?;
+        for item in self {
+            item.serialize(writer).or_fail()?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Deserialize> Deserialize for Vec<T> {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let n = usize::deserialize(reader).or_fail()?;
+        (0..n).map(|_| T::deserialize(reader).or_fail()).collect()
+    }
+}
+
+impl Serialize for String {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        u16::try_from(self.len())
+            .or_fail()?
+            .serialize(writer)
+            .or_fail()?;
+        writer.write_all(self.as_bytes()).or_fail()
+    }
+}
+
+impl Deserialize for String {
+    fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
+        let n = u16::deserialize(reader).or_fail()?;
+        let mut buf = vec![0; usize::from(n)];
+        reader.read_exact(&mut buf).or_fail()?;
+        String::from_utf8(buf).or_fail()
+    }
+}
+
 impl Serialize for bool {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         writer.write_u8(*self as u8).or_fail()
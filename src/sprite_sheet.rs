@@ -0,0 +1,170 @@
+use crate::model::Models;
+
+/// JSON description of the sprite sheet (horizontal strip) written by `Models::to_png()` when APNG is off.
+///
+/// The layout follows Aseprite's "Array" JSON format, which is also read by TexturePacker-compatible loaders.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpriteSheet {
+    pub frames: Vec<Frame>,
+    pub meta: Meta,
+}
+
+impl SpriteSheet {
+    pub fn new(models: &Models) -> Self {
+        let config = &models.config;
+        let base_size = config.frame.get_base_region().size();
+        let scale = u32::from(config.export_scale.get());
+        let size = Size {
+            w: u32::from(base_size.width) * scale,
+            h: u32::from(base_size.height) * scale,
+        };
+        let frame_count = usize::from(config.animation.enabled_frame_count());
+
+        let frames = (0..frame_count)
+            .map(|i| Frame {
+                filename: format!("pixcil {i}"),
+                frame: Rect {
+                    x: size.w * i as u32,
+                    y: 0,
+                    w: size.w,
+                    h: size.h,
+                },
+                rotated: false,
+                trimmed: false,
+                sprite_source_size: Rect {
+                    x: 0,
+                    y: 0,
+                    w: size.w,
+                    h: size.h,
+                },
+                source_size: size,
+                duration: config.frame_duration(i).as_millis() as u32,
+            })
+            .collect();
+        // Tags can refer to frames beyond the current frame count (e.g., after frames are removed).
+        let last_frame = frame_count as u16 - 1;
+        let frame_tags = config
+            .animation_tags
+            .tags()
+            .iter()
+            .filter(|tag| tag.start <= last_frame)
+            .map(|tag| FrameTag {
+                name: tag.name.clone(),
+                from: tag.start,
+                to: tag.end.min(last_frame),
+                direction: tag.direction.name(),
+            })
+            .collect();
+        Self {
+            frames,
+            meta: Meta {
+                app: env!("CARGO_PKG_HOMEPAGE"),
+                version: env!("CARGO_PKG_VERSION"),
+                format: if models.is_indexed_png() {
+                    "I8"
+                } else {
+                    "RGBA8888"
+                },
+                size: Size {
+                    w: size.w * frame_count as u32,
+                    h: size.h,
+                },
+                scale: scale.to_string(),
+                frame_tags,
+            },
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Frame {
+    pub filename: String,
+    pub frame: Rect,
+    pub rotated: bool,
+    pub trimmed: bool,
+    pub sprite_source_size: Rect,
+    pub source_size: Size,
+
+    /// Milliseconds.
+    pub duration: u32,
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct Size {
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    pub app: &'static str,
+    pub version: &'static str,
+    pub format: &'static str,
+    pub size: Size,
+    pub scale: String,
+    pub frame_tags: Vec<FrameTag>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct FrameTag {
+    pub name: String,
+    pub from: u16,
+    pub to: u16,
+    pub direction: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sprite_sheet_json_works() {
+        let mut models = Models::default();
+        let config = &mut models.config;
+        config.animation.set_enabled(true);
+        config.animation.set_frame_count(3);
+        for tag in ["walk 0-1", "jump 1-5 pingpong", "gone 3-4"] {
+            let tag = tag.parse().expect("valid tag");
+            config.animation_tags.add(tag).expect("add tag");
+        }
+
+        let json = serde_json::to_value(SpriteSheet::new(&models)).expect("serialize");
+        assert_eq!(json["frames"].as_array().map(|a| a.len()), Some(3));
+        assert_eq!(
+            json["frames"][1],
+            serde_json::json!({
+                "filename": "pixcil 1",
+                "frame": {"x": 64, "y": 0, "w": 64, "h": 64},
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": {"x": 0, "y": 0, "w": 64, "h": 64},
+                "sourceSize": {"w": 64, "h": 64},
+                "duration": 100
+            })
+        );
+        assert_eq!(json["meta"]["size"], serde_json::json!({"w": 192, "h": 64}));
+
+        // The empty canvas has a single (transparent) color, so the PNG is indexed.
+        assert_eq!(json["meta"]["format"], "I8");
+
+        // Tags are clamped to the frame count (or dropped if they start beyond it).
+        assert_eq!(
+            json["meta"]["frameTags"],
+            serde_json::json!([
+                {"name": "walk", "from": 0, "to": 1, "direction": "forward"},
+                {"name": "jump", "from": 1, "to": 2, "direction": "pingpong"}
+            ])
+        );
+    }
+}
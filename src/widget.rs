@@ -3,15 +3,20 @@ use orfail::{OrFail, Result};
 use pagurus::image::Canvas;
 use pagurus::spatial::{Position, Region, Size};
 
+pub mod animation_config;
 pub mod block;
 pub mod bottom_bar;
 pub mod button;
+pub mod choice;
 pub mod color_config;
 pub mod color_palette;
 pub mod color_selector;
 pub mod config;
+pub mod dither_tile;
+pub mod drawing_config;
 pub mod frame_size;
 pub mod hsv_selector;
+pub mod layers;
 pub mod manipulate;
 pub mod manipulate_tool;
 pub mod move_camera;
@@ -22,10 +27,12 @@ pub mod pixel_size;
 pub mod preview;
 pub mod rgb_selector;
 pub mod save_load;
+pub mod second_color;
 pub mod select_box;
 pub mod side_bar;
 pub mod size_box;
 pub mod slider;
+pub mod timeline;
 pub mod toggle;
 pub mod tool_box;
 pub mod undo_redo;
@@ -0,0 +1,260 @@
+use super::{
+    FixedSizeWidget, VariableSizeWidget, Widget, block::BlockWidget, button::ButtonWidget,
+    number_box::NumberBoxWidget, toggle::ToggleWidget,
+};
+use crate::{
+    app::App,
+    asset::{ButtonKind, IconId},
+    event::{Event, InputId},
+    model::config::{AnimationTag, OnionSkin},
+    region_ext::RegionExt,
+};
+use orfail::{OrFail, Result};
+use pagurus::image::Canvas;
+use pagurus::spatial::{Position, Region, Size};
+
+const MARGIN_X: u32 = 8;
+const MARGIN_Y: u32 = 14;
+
+/// Settings of the animation frames (animation tags and onion skin).
+#[derive(Debug)]
+pub struct AnimationConfigWidget {
+    region: Region,
+
+    // Animation tag settings
+    play_tag: BlockWidget<NumberBoxWidget>,
+    add_tag: BlockWidget<ButtonWidget>,
+    delete_tag: BlockWidget<ButtonWidget>,
+    tag_input: Option<InputId>,
+
+    // Onion skin settings
+    onion_prev: BlockWidget<NumberBoxWidget>,
+    onion_next: BlockWidget<NumberBoxWidget>,
+    onion_opacity: BlockWidget<NumberBoxWidget>,
+    onion_tint: BlockWidget<ToggleWidget>,
+}
+
+impl AnimationConfigWidget {
+    pub fn new(app: &App) -> Self {
+        let onion_skin = app.models().config.onion_skin;
+        Self {
+            region: Region::default(),
+
+            // Animation tags
+            play_tag: Self::play_tag_widget(app),
+            add_tag: BlockWidget::new(
+                "ADD TAG".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Import),
+            ),
+            delete_tag: BlockWidget::new(
+                "DELETE TAG".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Cut),
+            ),
+            tag_input: None,
+
+            // Onion skin
+            onion_prev: BlockWidget::new(
+                "ONION PREV".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    0,
+                    u32::from(onion_skin.prev_frames),
+                    u32::from(OnionSkin::MAX_FRAMES),
+                ),
+            ),
+            onion_next: BlockWidget::new(
+                "ONION NEXT".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    0,
+                    u32::from(onion_skin.next_frames),
+                    u32::from(OnionSkin::MAX_FRAMES),
+                ),
+            ),
+            onion_opacity: BlockWidget::new(
+                "ONION ALPHA".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    0,
+                    u32::from(onion_skin.opacity),
+                    u32::from(OnionSkin::MAX_OPACITY),
+                ),
+            ),
+            onion_tint: BlockWidget::new(
+                "ONION TINT".parse().expect("unreachable"),
+                ToggleWidget::new(onion_skin.tint),
+            ),
+        }
+    }
+
+    fn play_tag_widget(app: &App) -> BlockWidget<NumberBoxWidget> {
+        let tags = &app.models().config.animation_tags;
+        BlockWidget::new(
+            "PLAY TAG".parse().expect("unreachable"),
+            NumberBoxWidget::new(
+                0, // Play all frames
+                tags.selected_index().map_or(0, |i| i as u32 + 1),
+                tags.tags().len() as u32,
+            ),
+        )
+    }
+
+    fn reset_play_tag(&mut self, app: &mut App) {
+        let region = self.play_tag.region();
+        self.play_tag = Self::play_tag_widget(app);
+        self.play_tag.set_region(app, region);
+        app.request_redraw(region);
+    }
+}
+
+impl Widget for AnimationConfigWidget {
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn render(&self, app: &App, canvas: &mut Canvas) {
+        // Animation tags
+        self.play_tag.render_if_need(app, canvas);
+        self.add_tag.render_if_need(app, canvas);
+        self.delete_tag.render_if_need(app, canvas);
+
+        // Onion skin
+        self.onion_prev.render_if_need(app, canvas);
+        self.onion_next.render_if_need(app, canvas);
+        self.onion_opacity.render_if_need(app, canvas);
+        self.onion_tint.render_if_need(app, canvas);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
+        // Animation tags
+        self.play_tag.handle_event(app, event).or_fail()?;
+        let selected = (self.play_tag.body().value() as usize).checked_sub(1);
+        app.models_mut().config.animation_tags.select(selected);
+
+        self.add_tag.handle_event(app, event).or_fail()?;
+        if self.add_tag.body_mut().take_clicked(app) {
+            self.tag_input = Some(app.enqueue_input_text_request(String::new(), None));
+        }
+        if let Event::Input { id, text } = event
+            && self.tag_input == Some(*id)
+        {
+            self.tag_input = None;
+            let result = text
+                .parse::<AnimationTag>()
+                .map_err(|_| "expected NAME START-END [forward|reverse|pingpong]".to_owned())
+                .and_then(|tag| {
+                    let tags = &mut app.models_mut().config.animation_tags;
+                    tags.add(tag).map_err(|e| e.message)
+                });
+            match result {
+                Ok(()) => self.reset_play_tag(app),
+                Err(error) => {
+                    // The rejected text is input again so that the user can fix it.
+                    log::debug!("invalid animation tag {text:?}: {error}");
+                    let text = text.clone();
+                    let id = app.enqueue_input_text_request(text, Some(error));
+                    self.tag_input = Some(id);
+                }
+            }
+        }
+
+        self.delete_tag.handle_event(app, event).or_fail()?;
+        if self.delete_tag.body_mut().take_clicked(app) {
+            app.models_mut().config.animation_tags.remove_selected();
+            self.reset_play_tag(app);
+        }
+
+        // Onion skin
+        let onion_skin = app.models().config.onion_skin;
+        self.onion_prev.handle_event(app, event).or_fail()?;
+        self.onion_next.handle_event(app, event).or_fail()?;
+        self.onion_opacity.handle_event(app, event).or_fail()?;
+        self.onion_tint.handle_event(app, event).or_fail()?;
+        app.models_mut().config.onion_skin = OnionSkin {
+            prev_frames: self.onion_prev.body().value() as u8,
+            next_frames: self.onion_next.body().value() as u8,
+            opacity: self.onion_opacity.body().value() as u8,
+            tint: self.onion_tint.body().is_on(),
+        };
+        if onion_skin != app.models().config.onion_skin {
+            app.request_redraw(app.screen_size().to_region());
+        }
+
+        Ok(())
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![
+            // Animation tags
+            &mut self.play_tag,
+            &mut self.add_tag,
+            &mut self.delete_tag,
+            // Onion skin
+            &mut self.onion_prev,
+            &mut self.onion_next,
+            &mut self.onion_opacity,
+            &mut self.onion_tint,
+        ]
+    }
+}
+
+impl FixedSizeWidget for AnimationConfigWidget {
+    fn requiring_size(&self, app: &App) -> Size {
+        // Animation tags
+        let mut row1 = self.play_tag.requiring_size(app);
+        row1.width += MARGIN_X + self.add_tag.requiring_size(app).width;
+        row1.width += MARGIN_X + self.delete_tag.requiring_size(app).width;
+
+        // Onion skin
+        let mut row2 = self.onion_prev.requiring_size(app);
+        row2.width += MARGIN_X + self.onion_next.requiring_size(app).width;
+        row2.width += MARGIN_X + self.onion_opacity.requiring_size(app).width;
+        row2.width += MARGIN_X + self.onion_tint.requiring_size(app).width;
+
+        let rows = [row1, row2];
+        Size::from_wh(
+            rows.iter().map(|row| row.width).max().unwrap_or_default(),
+            rows.iter().map(|row| row.height).sum::<u32>() + MARGIN_Y * (rows.len() as u32 - 1),
+        ) + MARGIN_X * 2
+    }
+
+    fn set_position(&mut self, app: &App, position: Position) {
+        self.region = Region::new(position, self.requiring_size(app));
+
+        let mut region = self.region.without_margin(MARGIN_X);
+
+        // Animation tags
+        let mut play_tag_region = region;
+        play_tag_region.size = self.play_tag.requiring_size(app);
+        self.play_tag.set_region(app, play_tag_region);
+
+        let mut add_tag_region = region;
+        add_tag_region.position.x = play_tag_region.end().x + MARGIN_X as i32;
+        add_tag_region.size = self.add_tag.requiring_size(app);
+        self.add_tag.set_region(app, add_tag_region);
+
+        let mut delete_tag_region = region;
+        delete_tag_region.position.x = add_tag_region.end().x + MARGIN_X as i32;
+        delete_tag_region.size = self.delete_tag.requiring_size(app);
+        self.delete_tag.set_region(app, delete_tag_region);
+
+        region.consume_y(play_tag_region.size.height + MARGIN_Y);
+
+        // Onion skin
+        let mut onion_prev_region = region;
+        onion_prev_region.size = self.onion_prev.requiring_size(app);
+        self.onion_prev.set_region(app, onion_prev_region);
+
+        let mut onion_next_region = region;
+        onion_next_region.position.x = onion_prev_region.end().x + MARGIN_X as i32;
+        onion_next_region.size = self.onion_next.requiring_size(app);
+        self.onion_next.set_region(app, onion_next_region);
+
+        let mut onion_opacity_region = region;
+        onion_opacity_region.position.x = onion_next_region.end().x + MARGIN_X as i32;
+        onion_opacity_region.size = self.onion_opacity.requiring_size(app);
+        self.onion_opacity.set_region(app, onion_opacity_region);
+
+        let mut onion_tint_region = region;
+        onion_tint_region.position.x = onion_opacity_region.end().x + MARGIN_X as i32;
+        onion_tint_region.size = self.onion_tint.requiring_size(app);
+        self.onion_tint.set_region(app, onion_tint_region);
+    }
+}
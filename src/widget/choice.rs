@@ -0,0 +1,152 @@
+use super::{FixedSizeWidget, Widget};
+use crate::{
+    app::App,
+    asset::Text,
+    canvas_ext::CanvasExt,
+    color,
+    event::{Event, MouseAction},
+    model::config::{BlendMode, DitherPattern, FillMode, GradientStyle},
+    region_ext::RegionExt,
+};
+use pagurus::image::Canvas;
+use pagurus::{
+    Result,
+    spatial::{Contains, Position, Region, Size},
+};
+
+const PADDING: u32 = 8;
+
+/// A value that is selected from a fixed list of named options.
+pub trait Choice: std::fmt::Debug + Copy + PartialEq + 'static {
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+
+    fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&x| x == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl Choice for BlendMode {
+    const ALL: &'static [Self] = &BlendMode::ALL;
+
+    fn name(self) -> &'static str {
+        BlendMode::name(self)
+    }
+}
+
+impl Choice for FillMode {
+    const ALL: &'static [Self] = &FillMode::ALL;
+
+    fn name(self) -> &'static str {
+        FillMode::name(self)
+    }
+}
+
+impl Choice for DitherPattern {
+    const ALL: &'static [Self] = &DitherPattern::ALL;
+
+    fn name(self) -> &'static str {
+        DitherPattern::name(self)
+    }
+}
+
+impl Choice for GradientStyle {
+    const ALL: &'static [Self] = &GradientStyle::ALL;
+
+    fn name(self) -> &'static str {
+        GradientStyle::name(self)
+    }
+}
+
+/// Shows the name of a choice and switches to the next one when clicked.
+#[derive(Debug)]
+pub struct ChoiceWidget<T> {
+    region: Region,
+    value: T,
+    focused: bool,
+}
+
+impl<T: Choice> ChoiceWidget<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            region: Region::default(),
+            value,
+            focused: false,
+        }
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+}
+
+impl<T: Choice> Widget for ChoiceWidget<T> {
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn render(&self, app: &App, canvas: &mut Canvas) {
+        if self.focused {
+            canvas.fill_rectangle(self.region.without_margin(4), color::TEXT_BOX_FOCUSED);
+        } else {
+            canvas.fill_rectangle(self.region.without_margin(4), color::TEXT_BOX_UNFOCUSED);
+        }
+
+        let text: Text = self.value.name().parse().expect("unreachable");
+        let mut position = self.region.position;
+        position.x += PADDING as i32;
+        position.y += (self.region.size.height - text.size().height) as i32 / 2;
+        canvas
+            .offset(position)
+            .draw_text(&text, &app.assets().alphabet_10x14);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
+        let prev_focused = self.focused;
+        match event {
+            Event::Mouse {
+                action,
+                position,
+                consumed: false,
+                ..
+            } if self.region.contains(position) => {
+                self.focused = true;
+                if *action == MouseAction::Up {
+                    self.value = self.value.next();
+                    app.request_redraw(self.region);
+                }
+            }
+            Event::Mouse { .. } => {
+                self.focused = false;
+            }
+            _ => {}
+        }
+
+        event.consume_if_contained(self.region);
+        if prev_focused != self.focused {
+            app.request_redraw(self.region);
+        }
+        Ok(())
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![]
+    }
+}
+
+impl<T: Choice> FixedSizeWidget for ChoiceWidget<T> {
+    fn requiring_size(&self, app: &App) -> Size {
+        // Enough to show the longest name.
+        let max_name_len = T::ALL.iter().map(|x| x.name().len()).max().unwrap_or(0) as u32;
+        Size::from_wh(
+            max_name_len * 12 - 2 + PADDING * 2,
+            app.assets().number_box.size().height,
+        )
+    }
+
+    fn set_position(&mut self, app: &App, position: Position) {
+        self.region = Region::new(position, self.requiring_size(app));
+    }
+}
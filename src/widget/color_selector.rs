@@ -101,10 +101,10 @@ impl ColorSelectorWidget {
 
     fn cancel_color_replace_if_need(&mut self, app: &mut App) -> Result<()> {
         if self.replaced {
-            let config = app.models().config.clone();
-            app.models_mut()
+            let models = app.models_mut();
+            models
                 .pixel_canvas
-                .undo_command(&config)
+                .undo_command(&mut models.config)
                 .or_fail()?;
             app.request_redraw(app.screen_size().to_region());
             self.replaced = false;
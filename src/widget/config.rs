@@ -1,9 +1,21 @@
 use super::frame_size::FrameSizeWidget;
 use super::{
-    FixedSizeWidget, VariableSizeWidget, Widget, block::BlockWidget, number_box::NumberBoxWidget,
-    pixel_size::PixelSizeWidget, toggle::ToggleWidget,
+    FixedSizeWidget, VariableSizeWidget, Widget, block::BlockWidget, button::ButtonWidget,
+    choice::ChoiceWidget, number_box::NumberBoxWidget, pixel_size::PixelSizeWidget,
+    toggle::ToggleWidget,
+};
+use crate::{
+    app::App,
+    asset::{ButtonKind, IconId},
+    event::Event,
+    io::IoRequest,
+    model::config::{Animation, BlendMode, ExportScale, FrameDurations, LayerAttributes, MaxUndos},
+    region_ext::RegionExt,
+    window::{
+        animation_config::AnimationConfigWindow, drawing_config::DrawingConfigWindow,
+        layers::LayersWindow,
+    },
 };
-use crate::{app::App, event::Event, model::config::Animation, region_ext::RegionExt};
 use orfail::{OrFail, Result};
 use pagurus::image::Canvas;
 use pagurus::spatial::{Position, Region, Size};
@@ -26,12 +38,34 @@ pub struct ConfigWidget {
 
     // Layer / animation settings
     layer_enable: BlockWidget<ToggleWidget>,
+    edit_layers: BlockWidget<ButtonWidget>,
     animation_enable: BlockWidget<ToggleWidget>,
     fps: BlockWidget<NumberBoxWidget>,
     apng: BlockWidget<ToggleWidget>,
+    frame_duration: BlockWidget<NumberBoxWidget>,
+
+    // Layer attribute settings (of the current layer)
+    layer_hidden: BlockWidget<ToggleWidget>,
+    layer_opacity: BlockWidget<NumberBoxWidget>,
+    layer_locked: BlockWidget<ToggleWidget>,
+    layer_blend_mode: BlockWidget<ChoiceWidget<BlendMode>>,
 
     // Gesture settings
     gesture: BlockWidget<ToggleWidget>,
+    pixel_perfect: BlockWidget<ToggleWidget>,
+
+    // History settings
+    persist_undo_history: BlockWidget<ToggleWidget>,
+    max_undos: BlockWidget<NumberBoxWidget>,
+
+    // Export / import settings
+    export_scale: BlockWidget<NumberBoxWidget>,
+    export_gif: BlockWidget<ButtonWidget>,
+    import_sprite_sheet: BlockWidget<ButtonWidget>,
+
+    // Sub-window buttons
+    drawing_options: BlockWidget<ButtonWidget>,
+    animation_options: BlockWidget<ButtonWidget>,
 }
 
 impl ConfigWidget {
@@ -41,8 +75,21 @@ impl ConfigWidget {
         let silhouette_preview = app.models().config.silhouette_preview;
         let layer = app.models().config.layer;
         let animation = app.models().config.animation;
+        let current_frame = app.models().config.camera.current_frame(app);
+        let frame_duration = app
+            .models()
+            .config
+            .frame_durations
+            .get_millis(current_frame)
+            .unwrap_or(0);
+        let current_layer = app.models().config.camera.current_layer(app);
+        let layer_attrs = app.models().config.layer_attrs.get(current_layer);
         let apng = app.models().config.apng();
         let gesture = app.models().config.gesture;
+        let pixel_perfect = app.models().config.pixel_perfect;
+        let persist_undo_history = app.models().config.persist_undo_history;
+        let max_undos = app.models().config.max_undos;
+        let export_scale = app.models().config.export_scale;
         Self {
             region: Region::default(),
 
@@ -75,6 +122,10 @@ impl ConfigWidget {
                 "LAYER".parse().expect("unreachable"),
                 ToggleWidget::new(layer.is_enabled()),
             ),
+            edit_layers: BlockWidget::new(
+                "LAYERS".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Settings),
+            ),
             animation_enable: BlockWidget::new(
                 "ANIMATION".parse().expect("unreachable"),
                 ToggleWidget::new(animation.is_enabled()),
@@ -91,12 +142,93 @@ impl ConfigWidget {
                 "APNG".parse().expect("unreachable"),
                 ToggleWidget::new(apng),
             ),
+            frame_duration: BlockWidget::new(
+                "FRAME MSEC".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    0, // Follow the FPS setting
+                    u32::from(frame_duration),
+                    u32::from(FrameDurations::MAX_MILLIS),
+                ),
+            ),
+
+            // Layer attributes
+            layer_hidden: BlockWidget::new(
+                "HIDE LAYER".parse().expect("unreachable"),
+                ToggleWidget::new(layer_attrs.hidden),
+            ),
+            layer_opacity: BlockWidget::new(
+                "OPACITY".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    0,
+                    u32::from(layer_attrs.opacity),
+                    u32::from(LayerAttributes::MAX_OPACITY),
+                ),
+            ),
+            layer_locked: BlockWidget::new(
+                "LOCK LAYER".parse().expect("unreachable"),
+                ToggleWidget::new(layer_attrs.locked),
+            ),
+            layer_blend_mode: BlockWidget::new(
+                "BLEND".parse().expect("unreachable"),
+                ChoiceWidget::new(layer_attrs.blend_mode),
+            ),
 
             // Gesture
             gesture: BlockWidget::new(
                 "GESTURE".parse().expect("unreachable"),
                 ToggleWidget::new(gesture),
             ),
+            pixel_perfect: BlockWidget::new(
+                "PIXEL PERFECT".parse().expect("unreachable"),
+                ToggleWidget::new(pixel_perfect),
+            ),
+
+            // History
+            persist_undo_history: BlockWidget::new(
+                "SAVE HISTORY".parse().expect("unreachable"),
+                ToggleWidget::new(persist_undo_history),
+            ),
+            max_undos: BlockWidget::new(
+                "MAX UNDOS".parse().expect("unreachable"),
+                NumberBoxWidget::new(MaxUndos::MIN, max_undos.get(), MaxUndos::MAX),
+            ),
+
+            // Export / import
+            export_scale: BlockWidget::new(
+                "EXPORT SCALE".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    ExportScale::MIN as u32,
+                    export_scale.get() as u32,
+                    ExportScale::MAX as u32,
+                ),
+            ),
+            export_gif: BlockWidget::new(
+                "EXPORT GIF".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Save),
+            ),
+            import_sprite_sheet: BlockWidget::new(
+                "IMPORT SHEET".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Load),
+            ),
+
+            // Sub-windows
+            drawing_options: BlockWidget::new(
+                "DRAW OPTIONS".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Settings),
+            ),
+            animation_options: BlockWidget::new(
+                "FRAME OPTIONS".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Settings),
+            ),
+        }
+    }
+
+    fn layer_attributes(&self) -> LayerAttributes {
+        LayerAttributes {
+            hidden: self.layer_hidden.body().is_on(),
+            opacity: self.layer_opacity.body().value() as u8,
+            locked: self.layer_locked.body().is_on(),
+            blend_mode: self.layer_blend_mode.body().value(),
         }
     }
 }
@@ -118,12 +250,34 @@ impl Widget for ConfigWidget {
 
         // Layer / animation
         self.layer_enable.render_if_need(app, canvas);
+        self.edit_layers.render_if_need(app, canvas);
         self.animation_enable.render_if_need(app, canvas);
         self.fps.render_if_need(app, canvas);
         self.apng.render_if_need(app, canvas);
+        self.frame_duration.render_if_need(app, canvas);
+
+        // Layer attributes
+        self.layer_hidden.render_if_need(app, canvas);
+        self.layer_opacity.render_if_need(app, canvas);
+        self.layer_locked.render_if_need(app, canvas);
+        self.layer_blend_mode.render_if_need(app, canvas);
 
         // Gesture
         self.gesture.render_if_need(app, canvas);
+        self.pixel_perfect.render_if_need(app, canvas);
+
+        // History
+        self.persist_undo_history.render_if_need(app, canvas);
+        self.max_undos.render_if_need(app, canvas);
+
+        // Export / import
+        self.export_scale.render_if_need(app, canvas);
+        self.export_gif.render_if_need(app, canvas);
+        self.import_sprite_sheet.render_if_need(app, canvas);
+
+        // Sub-windows
+        self.drawing_options.render_if_need(app, canvas);
+        self.animation_options.render_if_need(app, canvas);
     }
 
     fn handle_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
@@ -135,6 +289,10 @@ impl Widget for ConfigWidget {
             .frame
             .set_from_pixel_size(self.frame_size.body().value());
         if frame != app.models().config.frame {
+            // The symmetry axes may be outside of the shrunk frame.
+            let config = &mut app.models_mut().config;
+            let frame_size = config.frame.get_base_region().size();
+            config.symmetry.clamp_axes(frame_size);
             app.request_redraw(app.screen_size().to_region());
         }
 
@@ -172,6 +330,11 @@ impl Widget for ConfigWidget {
             app.request_redraw(app.screen_size().to_region());
         }
 
+        self.edit_layers.handle_event(app, event).or_fail()?;
+        if self.edit_layers.body_mut().take_clicked(app) {
+            app.spawn_window(LayersWindow::new(app)).or_fail()?;
+        }
+
         let animation = app.models_mut().config.animation;
         self.animation_enable.handle_event(app, event).or_fail()?;
         app.models_mut()
@@ -188,14 +351,107 @@ impl Widget for ConfigWidget {
         self.apng.handle_event(app, event).or_fail()?;
         app.models_mut().config.apng = Some(self.apng.body().is_on());
 
+        let frame_durations = app.models().config.frame_durations.clone();
+        self.frame_duration.handle_event(app, event).or_fail()?;
+        let current_frame = app.models().config.camera.current_frame(app);
+        let millis = self.frame_duration.body().value() as u16;
+        app.models_mut()
+            .config
+            .frame_durations
+            .set_millis(current_frame, (millis > 0).then_some(millis));
+        if frame_durations != app.models().config.frame_durations {
+            app.request_redraw(app.screen_size().to_region());
+        }
+
         if animation != app.models_mut().config.animation {
             app.request_redraw(app.screen_size().to_region());
         }
 
+        // Layer attributes
+        let current_layer = app.models().config.camera.current_layer(app);
+        let layer_attrs = app.models().config.layer_attrs;
+        let prev_widget_attrs = self.layer_attributes();
+        self.layer_hidden.handle_event(app, event).or_fail()?;
+        self.layer_opacity.handle_event(app, event).or_fail()?;
+        self.layer_locked.handle_event(app, event).or_fail()?;
+        self.layer_blend_mode.handle_event(app, event).or_fail()?;
+        if let Some(attrs) = app.models_mut().config.layer_attrs.get_mut(current_layer) {
+            // Only changed values are written, as the layers may have been rearranged via `LayersWindow`.
+            let widget_attrs = self.layer_attributes();
+            if widget_attrs.hidden != prev_widget_attrs.hidden {
+                attrs.hidden = widget_attrs.hidden;
+            }
+            if widget_attrs.opacity != prev_widget_attrs.opacity {
+                attrs.opacity = widget_attrs.opacity;
+            }
+            if widget_attrs.locked != prev_widget_attrs.locked {
+                attrs.locked = widget_attrs.locked;
+            }
+            if widget_attrs.blend_mode != prev_widget_attrs.blend_mode {
+                attrs.blend_mode = widget_attrs.blend_mode;
+            }
+        }
+        if layer_attrs != app.models().config.layer_attrs {
+            app.request_redraw(app.screen_size().to_region());
+        }
+
         // Gesture
         self.gesture.handle_event(app, event).or_fail()?;
         app.models_mut().config.gesture = self.gesture.body().is_on();
 
+        self.pixel_perfect.handle_event(app, event).or_fail()?;
+        app.models_mut().config.pixel_perfect = self.pixel_perfect.body().is_on();
+
+        // History
+        self.persist_undo_history
+            .handle_event(app, event)
+            .or_fail()?;
+        app.models_mut().config.persist_undo_history = self.persist_undo_history.body().is_on();
+
+        let max_undos = app.models().config.max_undos;
+        self.max_undos.handle_event(app, event).or_fail()?;
+        app.models_mut()
+            .config
+            .max_undos
+            .set(self.max_undos.body().value());
+        if max_undos != app.models().config.max_undos {
+            let config = app.models().config.clone();
+            app.models_mut()
+                .pixel_canvas
+                .forget_exceeded_commands(&config);
+        }
+
+        // Export / import
+        self.export_scale.handle_event(app, event).or_fail()?;
+        app.models_mut()
+            .config
+            .export_scale
+            .set(self.export_scale.body().value() as u8);
+
+        self.export_gif.handle_event(app, event).or_fail()?;
+        if self.export_gif.body_mut().take_clicked(app) {
+            app.enqueue_io_request(IoRequest::ExportGif);
+        }
+
+        self.import_sprite_sheet
+            .handle_event(app, event)
+            .or_fail()?;
+        if self.import_sprite_sheet.body_mut().take_clicked(app) {
+            app.enqueue_io_request(IoRequest::ImportSpriteSheet);
+        }
+
+        // Sub-windows
+        self.drawing_options.handle_event(app, event).or_fail()?;
+        if self.drawing_options.body_mut().take_clicked(app) {
+            app.spawn_window(DrawingConfigWindow::new(app)).or_fail()?;
+        }
+
+        self.animation_options.handle_event(app, event).or_fail()?;
+        if self.animation_options.body_mut().take_clicked(app) {
+            app.spawn_window(AnimationConfigWindow::new(app))
+                .or_fail()?;
+        }
+
         Ok(())
     }
 
@@ -210,11 +466,29 @@ impl Widget for ConfigWidget {
             &mut self.silhouette,
             // Layer / animation
             &mut self.layer_enable,
+            &mut self.edit_layers,
             &mut self.animation_enable,
             &mut self.fps,
             &mut self.apng,
+            &mut self.frame_duration,
+            // Layer attributes
+            &mut self.layer_hidden,
+            &mut self.layer_opacity,
+            &mut self.layer_locked,
+            &mut self.layer_blend_mode,
             // Gesture
             &mut self.gesture,
+            &mut self.pixel_perfect,
+            // History
+            &mut self.persist_undo_history,
+            &mut self.max_undos,
+            // Export / import
+            &mut self.export_scale,
+            &mut self.export_gif,
+            &mut self.import_sprite_sheet,
+            // Sub-windows
+            &mut self.drawing_options,
+            &mut self.animation_options,
         ]
     }
 }
@@ -224,9 +498,10 @@ impl FixedSizeWidget for ConfigWidget {
         // Frame size
         let row1 = self.frame_size.requiring_size(app);
 
-        // Pixel size / gesture
+        // Pixel size / gesture / pixel perfect
         let mut row2 = self.pixel_size.requiring_size(app);
         row2.width += MARGIN_X + self.gesture.requiring_size(app).width;
+        row2.width += MARGIN_X + self.pixel_perfect.requiring_size(app).width;
 
         // Preview
         let mut row3 = self.frame_preview.requiring_size(app);
@@ -235,13 +510,35 @@ impl FixedSizeWidget for ConfigWidget {
 
         // Layer / animation
         let mut row4 = self.layer_enable.requiring_size(app);
+        row4.width += MARGIN_X + self.edit_layers.requiring_size(app).width;
         row4.width += MARGIN_X + self.animation_enable.requiring_size(app).width;
         row4.width += MARGIN_X + self.fps.requiring_size(app).width;
         row4.width += MARGIN_X + self.apng.requiring_size(app).width;
+        row4.width += MARGIN_X + self.frame_duration.requiring_size(app).width;
+
+        // Layer attributes
+        let mut row5 = self.layer_hidden.requiring_size(app);
+        row5.width += MARGIN_X + self.layer_opacity.requiring_size(app).width;
+        row5.width += MARGIN_X + self.layer_locked.requiring_size(app).width;
+        row5.width += MARGIN_X + self.layer_blend_mode.requiring_size(app).width;
+
+        // History
+        let mut row6 = self.persist_undo_history.requiring_size(app);
+        row6.width += MARGIN_X + self.max_undos.requiring_size(app).width;
+
+        // Export / import
+        let mut row7 = self.export_scale.requiring_size(app);
+        row7.width += MARGIN_X + self.export_gif.requiring_size(app).width;
+        row7.width += MARGIN_X + self.import_sprite_sheet.requiring_size(app).width;
 
+        // Sub-windows
+        let mut row8 = self.drawing_options.requiring_size(app);
+        row8.width += MARGIN_X + self.animation_options.requiring_size(app).width;
+
+        let rows = [row1, row2, row3, row4, row5, row6, row7, row8];
         Size::from_wh(
-            row1.width.max(row2.width).max(row3.width).max(row4.width),
-            row1.height + MARGIN_Y + row2.height + MARGIN_Y + row3.height + MARGIN_Y + row4.height,
+            rows.iter().map(|row| row.width).max().unwrap_or_default(),
+            rows.iter().map(|row| row.height).sum::<u32>() + MARGIN_Y * (rows.len() as u32 - 1),
         ) + MARGIN_X * 2
     }
 
@@ -265,6 +562,11 @@ impl FixedSizeWidget for ConfigWidget {
         gesture_region.size = self.gesture.requiring_size(app);
         self.gesture.set_region(app, gesture_region);
 
+        let mut pixel_perfect_region = region;
+        pixel_perfect_region.position.x = gesture_region.end().x + MARGIN_X as i32;
+        pixel_perfect_region.size = self.pixel_perfect.requiring_size(app);
+        self.pixel_perfect.set_region(app, pixel_perfect_region);
+
         region.consume_y(gesture_region.size.height + MARGIN_Y);
 
         // Preview
@@ -290,8 +592,13 @@ impl FixedSizeWidget for ConfigWidget {
         layer_enable_region.size = self.layer_enable.requiring_size(app);
         self.layer_enable.set_region(app, layer_enable_region);
 
+        let mut edit_layers_region = region;
+        edit_layers_region.position.x = layer_enable_region.end().x + MARGIN_X as i32;
+        edit_layers_region.size = self.edit_layers.requiring_size(app);
+        self.edit_layers.set_region(app, edit_layers_region);
+
         let mut animation_enable_region = region;
-        animation_enable_region.position.x = layer_enable_region.end().x + MARGIN_X as i32;
+        animation_enable_region.position.x = edit_layers_region.end().x + MARGIN_X as i32;
         animation_enable_region.size = self.animation_enable.requiring_size(app);
         self.animation_enable
             .set_region(app, animation_enable_region);
@@ -305,5 +612,77 @@ impl FixedSizeWidget for ConfigWidget {
         apng_region.position.x = fps_region.end().x + MARGIN_X as i32;
         apng_region.size = self.apng.requiring_size(app);
         self.apng.set_region(app, apng_region);
+
+        let mut frame_duration_region = region;
+        frame_duration_region.position.x = apng_region.end().x + MARGIN_X as i32;
+        frame_duration_region.size = self.frame_duration.requiring_size(app);
+        self.frame_duration.set_region(app, frame_duration_region);
+
+        region.consume_y(layer_enable_region.size.height + MARGIN_Y);
+
+        // Layer attributes
+        let mut layer_hidden_region = region;
+        layer_hidden_region.size = self.layer_hidden.requiring_size(app);
+        self.layer_hidden.set_region(app, layer_hidden_region);
+
+        let mut layer_opacity_region = region;
+        layer_opacity_region.position.x = layer_hidden_region.end().x + MARGIN_X as i32;
+        layer_opacity_region.size = self.layer_opacity.requiring_size(app);
+        self.layer_opacity.set_region(app, layer_opacity_region);
+
+        let mut layer_locked_region = region;
+        layer_locked_region.position.x = layer_opacity_region.end().x + MARGIN_X as i32;
+        layer_locked_region.size = self.layer_locked.requiring_size(app);
+        self.layer_locked.set_region(app, layer_locked_region);
+
+        let mut layer_blend_mode_region = region;
+        layer_blend_mode_region.position.x = layer_locked_region.end().x + MARGIN_X as i32;
+        layer_blend_mode_region.size = self.layer_blend_mode.requiring_size(app);
+        self.layer_blend_mode
+            .set_region(app, layer_blend_mode_region);
+
+        region.consume_y(layer_hidden_region.size.height + MARGIN_Y);
+
+        // History
+        let mut persist_undo_history_region = region;
+        persist_undo_history_region.size = self.persist_undo_history.requiring_size(app);
+        self.persist_undo_history
+            .set_region(app, persist_undo_history_region);
+
+        let mut max_undos_region = region;
+        max_undos_region.position.x = persist_undo_history_region.end().x + MARGIN_X as i32;
+        max_undos_region.size = self.max_undos.requiring_size(app);
+        self.max_undos.set_region(app, max_undos_region);
+
+        region.consume_y(persist_undo_history_region.size.height + MARGIN_Y);
+
+        // Export / import
+        let mut export_scale_region = region;
+        export_scale_region.size = self.export_scale.requiring_size(app);
+        self.export_scale.set_region(app, export_scale_region);
+
+        let mut export_gif_region = region;
+        export_gif_region.position.x = export_scale_region.end().x + MARGIN_X as i32;
+        export_gif_region.size = self.export_gif.requiring_size(app);
+        self.export_gif.set_region(app, export_gif_region);
+
+        let mut import_sprite_sheet_region = region;
+        import_sprite_sheet_region.position.x = export_gif_region.end().x + MARGIN_X as i32;
+        import_sprite_sheet_region.size = self.import_sprite_sheet.requiring_size(app);
+        self.import_sprite_sheet
+            .set_region(app, import_sprite_sheet_region);
+
+        region.consume_y(export_scale_region.size.height + MARGIN_Y);
+
+        // Sub-windows
+        let mut drawing_options_region = region;
+        drawing_options_region.size = self.drawing_options.requiring_size(app);
+        self.drawing_options.set_region(app, drawing_options_region);
+
+        let mut animation_options_region = region;
+        animation_options_region.position.x = drawing_options_region.end().x + MARGIN_X as i32;
+        animation_options_region.size = self.animation_options.requiring_size(app);
+        self.animation_options
+            .set_region(app, animation_options_region);
     }
 }
@@ -0,0 +1,103 @@
+use super::{FixedSizeWidget, Widget};
+use crate::{
+    app::App,
+    canvas_ext::CanvasExt,
+    color,
+    event::{Event, MouseAction},
+    model::config::Dither,
+    region_ext::RegionExt,
+};
+use pagurus::image::Canvas;
+use pagurus::{
+    Result,
+    spatial::{Contains, Position, Region, Size},
+};
+
+const MARGIN: u32 = 4;
+
+/// Shows the tile of the custom dither pattern and toggles the clicked cell.
+#[derive(Debug)]
+pub struct DitherTileWidget {
+    region: Region,
+    tile: u16,
+}
+
+impl DitherTileWidget {
+    pub fn new(tile: u16) -> Self {
+        Self {
+            region: Region::default(),
+            tile,
+        }
+    }
+
+    pub fn tile(&self) -> u16 {
+        self.tile
+    }
+
+    fn cell_size(&self) -> u32 {
+        self.region.without_margin(MARGIN).size.width / Dither::TILE_SIZE as u32
+    }
+
+    fn cell_region(&self, x: i16, y: i16) -> Region {
+        let size = self.cell_size();
+        let mut region = self.region.without_margin(MARGIN);
+        region.position.x += x as i32 * size as i32;
+        region.position.y += y as i32 * size as i32;
+        region.size = Size::square(size);
+        region
+    }
+
+    fn cells() -> impl Iterator<Item = (i16, i16)> {
+        (0..Dither::TILE_SIZE).flat_map(|y| (0..Dither::TILE_SIZE).map(move |x| (x, y)))
+    }
+}
+
+impl Widget for DitherTileWidget {
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn render(&self, _app: &App, canvas: &mut Canvas) {
+        canvas.fill_rectangle(
+            self.region.without_margin(MARGIN),
+            color::TEXT_BOX_UNFOCUSED,
+        );
+        for (i, (x, y)) in Self::cells().enumerate() {
+            if self.tile & (1 << i) != 0 {
+                canvas.fill_rectangle(self.cell_region(x, y), color::WINDOW_BORDER);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
+        if let Event::Mouse {
+            action: MouseAction::Up,
+            position,
+            consumed: false,
+            ..
+        } = event
+            && let Some(i) =
+                Self::cells().position(|(x, y)| self.cell_region(x, y).contains(position))
+        {
+            self.tile ^= 1 << i;
+            app.request_redraw(self.region);
+        }
+
+        event.consume_if_contained(self.region);
+        Ok(())
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![]
+    }
+}
+
+impl FixedSizeWidget for DitherTileWidget {
+    fn requiring_size(&self, app: &App) -> Size {
+        Size::square(app.assets().number_box.size().height)
+    }
+
+    fn set_position(&mut self, app: &App, position: Position) {
+        self.region = Region::new(position, self.requiring_size(app));
+    }
+}
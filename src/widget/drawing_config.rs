@@ -0,0 +1,400 @@
+use super::{
+    FixedSizeWidget, VariableSizeWidget, Widget, block::BlockWidget, choice::ChoiceWidget,
+    dither_tile::DitherTileWidget, number_box::NumberBoxWidget, second_color::SecondColorWidget,
+    toggle::ToggleWidget,
+};
+use crate::{
+    app::App,
+    event::Event,
+    model::config::{Dither, DitherPattern, FillMode, Gradient, GradientStyle, Symmetry},
+    region_ext::RegionExt,
+};
+use orfail::{OrFail, Result};
+use pagurus::image::Canvas;
+use pagurus::spatial::{Position, Region, Size};
+
+const MARGIN_X: u32 = 8;
+const MARGIN_Y: u32 = 14;
+
+/// Settings of the drawing tools (symmetry, fill, dither and gradient).
+#[derive(Debug)]
+pub struct DrawingConfigWidget {
+    region: Region,
+
+    // Symmetry settings
+    mirror_x: BlockWidget<ToggleWidget>,
+    axis_x: BlockWidget<NumberBoxWidget>,
+    mirror_y: BlockWidget<ToggleWidget>,
+    axis_y: BlockWidget<NumberBoxWidget>,
+
+    // Fill settings
+    fill_mode: BlockWidget<ChoiceWidget<FillMode>>,
+    fill_tolerance: BlockWidget<NumberBoxWidget>,
+    fill_diagonal: BlockWidget<ToggleWidget>,
+    fill_within_frame: BlockWidget<ToggleWidget>,
+
+    // Dither settings
+    dither_pattern: BlockWidget<ChoiceWidget<DitherPattern>>,
+    dither_density: BlockWidget<NumberBoxWidget>,
+    dither_tile: BlockWidget<DitherTileWidget>,
+    dither_two_color: BlockWidget<ToggleWidget>,
+    dither_second_color: BlockWidget<SecondColorWidget>,
+
+    // Gradient settings
+    gradient_steps: BlockWidget<NumberBoxWidget>,
+    gradient_style: BlockWidget<ChoiceWidget<GradientStyle>>,
+    gradient_end_color: BlockWidget<SecondColorWidget>,
+}
+
+impl DrawingConfigWidget {
+    pub fn new(app: &App) -> Self {
+        let symmetry = app.models().config.symmetry;
+        let fill_mode = app.models().config.fill_mode;
+        let fill_tolerance = app.models().config.fill_tolerance;
+        let fill_diagonal = app.models().config.fill_diagonal;
+        let fill_within_frame = app.models().config.fill_within_frame;
+        let dither = app.models().config.dither;
+        let gradient = app.models().config.gradient;
+        let frame_size = app.models().config.frame.get_base_region().size();
+        Self {
+            region: Region::default(),
+
+            // Symmetry (an axis of 0 means the center of the frame)
+            mirror_x: BlockWidget::new(
+                "MIRROR X".parse().expect("unreachable"),
+                ToggleWidget::new(symmetry.horizontal),
+            ),
+            axis_x: BlockWidget::new(
+                "AXIS X".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    0,
+                    u32::from(symmetry.axis_x.unwrap_or(0)),
+                    u32::from(frame_size.width),
+                ),
+            ),
+            mirror_y: BlockWidget::new(
+                "MIRROR Y".parse().expect("unreachable"),
+                ToggleWidget::new(symmetry.vertical),
+            ),
+            axis_y: BlockWidget::new(
+                "AXIS Y".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    0,
+                    u32::from(symmetry.axis_y.unwrap_or(0)),
+                    u32::from(frame_size.height),
+                ),
+            ),
+
+            // Fill
+            fill_mode: BlockWidget::new(
+                "FILL MODE".parse().expect("unreachable"),
+                ChoiceWidget::new(fill_mode),
+            ),
+            fill_tolerance: BlockWidget::new(
+                "TOLERANCE".parse().expect("unreachable"),
+                NumberBoxWidget::new(0, u32::from(fill_tolerance), u32::from(u8::MAX)),
+            ),
+            fill_diagonal: BlockWidget::new(
+                "DIAGONAL".parse().expect("unreachable"),
+                ToggleWidget::new(fill_diagonal),
+            ),
+            fill_within_frame: BlockWidget::new(
+                "IN FRAME".parse().expect("unreachable"),
+                ToggleWidget::new(fill_within_frame),
+            ),
+
+            // Dither
+            dither_pattern: BlockWidget::new(
+                "DITHER".parse().expect("unreachable"),
+                ChoiceWidget::new(dither.pattern),
+            ),
+            dither_density: BlockWidget::new(
+                "DENSITY".parse().expect("unreachable"),
+                NumberBoxWidget::new(0, u32::from(dither.density), u32::from(Dither::MAX_DENSITY)),
+            ),
+            dither_tile: BlockWidget::new(
+                "TILE".parse().expect("unreachable"),
+                DitherTileWidget::new(dither.tile),
+            ),
+            dither_two_color: BlockWidget::new(
+                "TWO COLOR".parse().expect("unreachable"),
+                ToggleWidget::new(dither.two_color),
+            ),
+            dither_second_color: BlockWidget::new(
+                "SECOND".parse().expect("unreachable"),
+                SecondColorWidget::new(
+                    |config| config.dither.second_color,
+                    |config, color| config.dither.second_color = color,
+                ),
+            ),
+
+            // Gradient
+            gradient_steps: BlockWidget::new(
+                "GRADIENT STEPS".parse().expect("unreachable"),
+                NumberBoxWidget::new(
+                    u32::from(Gradient::MIN_STEPS),
+                    u32::from(gradient.steps),
+                    u32::from(Gradient::MAX_STEPS),
+                ),
+            ),
+            gradient_style: BlockWidget::new(
+                "GRADIENT STYLE".parse().expect("unreachable"),
+                ChoiceWidget::new(gradient.style),
+            ),
+            gradient_end_color: BlockWidget::new(
+                "GRADIENT END".parse().expect("unreachable"),
+                SecondColorWidget::new(
+                    |config| config.gradient.end_color,
+                    |config, color| config.gradient.end_color = color,
+                ),
+            ),
+        }
+    }
+}
+
+impl Widget for DrawingConfigWidget {
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn render(&self, app: &App, canvas: &mut Canvas) {
+        // Symmetry
+        self.mirror_x.render_if_need(app, canvas);
+        self.axis_x.render_if_need(app, canvas);
+        self.mirror_y.render_if_need(app, canvas);
+        self.axis_y.render_if_need(app, canvas);
+
+        // Fill
+        self.fill_mode.render_if_need(app, canvas);
+        self.fill_tolerance.render_if_need(app, canvas);
+        self.fill_diagonal.render_if_need(app, canvas);
+        self.fill_within_frame.render_if_need(app, canvas);
+
+        // Dither
+        self.dither_pattern.render_if_need(app, canvas);
+        self.dither_density.render_if_need(app, canvas);
+        self.dither_tile.render_if_need(app, canvas);
+        self.dither_two_color.render_if_need(app, canvas);
+        self.dither_second_color.render_if_need(app, canvas);
+
+        // Gradient
+        self.gradient_steps.render_if_need(app, canvas);
+        self.gradient_style.render_if_need(app, canvas);
+        self.gradient_end_color.render_if_need(app, canvas);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
+        // Symmetry
+        let symmetry = app.models().config.symmetry;
+        self.mirror_x.handle_event(app, event).or_fail()?;
+        self.axis_x.handle_event(app, event).or_fail()?;
+        self.mirror_y.handle_event(app, event).or_fail()?;
+        self.axis_y.handle_event(app, event).or_fail()?;
+        let axis = |value: u32| (value != 0).then_some(value as u16);
+        app.models_mut().config.symmetry = Symmetry {
+            horizontal: self.mirror_x.body().is_on(),
+            vertical: self.mirror_y.body().is_on(),
+            axis_x: axis(self.axis_x.body().value()),
+            axis_y: axis(self.axis_y.body().value()),
+        };
+        if symmetry != app.models().config.symmetry {
+            app.request_redraw(app.screen_size().to_region());
+        }
+
+        // Fill
+        self.fill_mode.handle_event(app, event).or_fail()?;
+        app.models_mut().config.fill_mode = self.fill_mode.body().value();
+
+        self.fill_tolerance.handle_event(app, event).or_fail()?;
+        app.models_mut().config.fill_tolerance = self.fill_tolerance.body().value() as u8;
+
+        self.fill_diagonal.handle_event(app, event).or_fail()?;
+        app.models_mut().config.fill_diagonal = self.fill_diagonal.body().is_on();
+
+        self.fill_within_frame.handle_event(app, event).or_fail()?;
+        app.models_mut().config.fill_within_frame = self.fill_within_frame.body().is_on();
+
+        // Dither
+        let dither = app.models().config.dither;
+        self.dither_pattern.handle_event(app, event).or_fail()?;
+        self.dither_density.handle_event(app, event).or_fail()?;
+        self.dither_tile.handle_event(app, event).or_fail()?;
+        self.dither_two_color.handle_event(app, event).or_fail()?;
+        self.dither_second_color
+            .handle_event(app, event)
+            .or_fail()?;
+        let config = &mut app.models_mut().config;
+        config.dither.pattern = self.dither_pattern.body().value();
+        config.dither.density = self.dither_density.body().value() as u8;
+        config.dither.tile = self.dither_tile.body().tile();
+        config.dither.two_color = self.dither_two_color.body().is_on();
+        if dither != app.models().config.dither {
+            app.request_redraw(app.screen_size().to_region());
+        }
+
+        // Gradient
+        self.gradient_steps.handle_event(app, event).or_fail()?;
+        self.gradient_style.handle_event(app, event).or_fail()?;
+        self.gradient_end_color.handle_event(app, event).or_fail()?;
+        let gradient = &mut app.models_mut().config.gradient;
+        gradient.steps = self.gradient_steps.body().value() as u8;
+        gradient.style = self.gradient_style.body().value();
+
+        Ok(())
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![
+            // Symmetry
+            &mut self.mirror_x,
+            &mut self.axis_x,
+            &mut self.mirror_y,
+            &mut self.axis_y,
+            // Fill
+            &mut self.fill_mode,
+            &mut self.fill_tolerance,
+            &mut self.fill_diagonal,
+            &mut self.fill_within_frame,
+            // Dither
+            &mut self.dither_pattern,
+            &mut self.dither_density,
+            &mut self.dither_tile,
+            &mut self.dither_two_color,
+            &mut self.dither_second_color,
+            // Gradient
+            &mut self.gradient_steps,
+            &mut self.gradient_style,
+            &mut self.gradient_end_color,
+        ]
+    }
+}
+
+impl FixedSizeWidget for DrawingConfigWidget {
+    fn requiring_size(&self, app: &App) -> Size {
+        // Symmetry
+        let mut row1 = self.mirror_x.requiring_size(app);
+        row1.width += MARGIN_X + self.axis_x.requiring_size(app).width;
+        row1.width += MARGIN_X + self.mirror_y.requiring_size(app).width;
+        row1.width += MARGIN_X + self.axis_y.requiring_size(app).width;
+
+        // Fill
+        let mut row2 = self.fill_mode.requiring_size(app);
+        row2.width += MARGIN_X + self.fill_tolerance.requiring_size(app).width;
+        row2.width += MARGIN_X + self.fill_diagonal.requiring_size(app).width;
+        row2.width += MARGIN_X + self.fill_within_frame.requiring_size(app).width;
+
+        // Dither
+        let mut row3 = self.dither_pattern.requiring_size(app);
+        row3.width += MARGIN_X + self.dither_density.requiring_size(app).width;
+        row3.width += MARGIN_X + self.dither_tile.requiring_size(app).width;
+        row3.width += MARGIN_X + self.dither_two_color.requiring_size(app).width;
+        row3.width += MARGIN_X + self.dither_second_color.requiring_size(app).width;
+
+        // Gradient
+        let mut row4 = self.gradient_steps.requiring_size(app);
+        row4.width += MARGIN_X + self.gradient_style.requiring_size(app).width;
+        row4.width += MARGIN_X + self.gradient_end_color.requiring_size(app).width;
+
+        let rows = [row1, row2, row3, row4];
+        Size::from_wh(
+            rows.iter().map(|row| row.width).max().unwrap_or_default(),
+            rows.iter().map(|row| row.height).sum::<u32>() + MARGIN_Y * (rows.len() as u32 - 1),
+        ) + MARGIN_X * 2
+    }
+
+    fn set_position(&mut self, app: &App, position: Position) {
+        self.region = Region::new(position, self.requiring_size(app));
+
+        let mut region = self.region.without_margin(MARGIN_X);
+
+        // Symmetry
+        let mut mirror_x_region = region;
+        mirror_x_region.size = self.mirror_x.requiring_size(app);
+        self.mirror_x.set_region(app, mirror_x_region);
+
+        let mut axis_x_region = region;
+        axis_x_region.position.x = mirror_x_region.end().x + MARGIN_X as i32;
+        axis_x_region.size = self.axis_x.requiring_size(app);
+        self.axis_x.set_region(app, axis_x_region);
+
+        let mut mirror_y_region = region;
+        mirror_y_region.position.x = axis_x_region.end().x + MARGIN_X as i32;
+        mirror_y_region.size = self.mirror_y.requiring_size(app);
+        self.mirror_y.set_region(app, mirror_y_region);
+
+        let mut axis_y_region = region;
+        axis_y_region.position.x = mirror_y_region.end().x + MARGIN_X as i32;
+        axis_y_region.size = self.axis_y.requiring_size(app);
+        self.axis_y.set_region(app, axis_y_region);
+
+        region.consume_y(mirror_x_region.size.height + MARGIN_Y);
+
+        // Fill
+        let mut fill_mode_region = region;
+        fill_mode_region.size = self.fill_mode.requiring_size(app);
+        self.fill_mode.set_region(app, fill_mode_region);
+
+        let mut fill_tolerance_region = region;
+        fill_tolerance_region.position.x = fill_mode_region.end().x + MARGIN_X as i32;
+        fill_tolerance_region.size = self.fill_tolerance.requiring_size(app);
+        self.fill_tolerance.set_region(app, fill_tolerance_region);
+
+        let mut fill_diagonal_region = region;
+        fill_diagonal_region.position.x = fill_tolerance_region.end().x + MARGIN_X as i32;
+        fill_diagonal_region.size = self.fill_diagonal.requiring_size(app);
+        self.fill_diagonal.set_region(app, fill_diagonal_region);
+
+        let mut fill_within_frame_region = region;
+        fill_within_frame_region.position.x = fill_diagonal_region.end().x + MARGIN_X as i32;
+        fill_within_frame_region.size = self.fill_within_frame.requiring_size(app);
+        self.fill_within_frame
+            .set_region(app, fill_within_frame_region);
+
+        region.consume_y(fill_mode_region.size.height + MARGIN_Y);
+
+        // Dither
+        let mut dither_pattern_region = region;
+        dither_pattern_region.size = self.dither_pattern.requiring_size(app);
+        self.dither_pattern.set_region(app, dither_pattern_region);
+
+        let mut dither_density_region = region;
+        dither_density_region.position.x = dither_pattern_region.end().x + MARGIN_X as i32;
+        dither_density_region.size = self.dither_density.requiring_size(app);
+        self.dither_density.set_region(app, dither_density_region);
+
+        let mut dither_tile_region = region;
+        dither_tile_region.position.x = dither_density_region.end().x + MARGIN_X as i32;
+        dither_tile_region.size = self.dither_tile.requiring_size(app);
+        self.dither_tile.set_region(app, dither_tile_region);
+
+        let mut dither_two_color_region = region;
+        dither_two_color_region.position.x = dither_tile_region.end().x + MARGIN_X as i32;
+        dither_two_color_region.size = self.dither_two_color.requiring_size(app);
+        self.dither_two_color
+            .set_region(app, dither_two_color_region);
+
+        let mut dither_second_color_region = region;
+        dither_second_color_region.position.x = dither_two_color_region.end().x + MARGIN_X as i32;
+        dither_second_color_region.size = self.dither_second_color.requiring_size(app);
+        self.dither_second_color
+            .set_region(app, dither_second_color_region);
+
+        region.consume_y(dither_pattern_region.size.height + MARGIN_Y);
+
+        // Gradient
+        let mut gradient_steps_region = region;
+        gradient_steps_region.size = self.gradient_steps.requiring_size(app);
+        self.gradient_steps.set_region(app, gradient_steps_region);
+
+        let mut gradient_style_region = region;
+        gradient_style_region.position.x = gradient_steps_region.end().x + MARGIN_X as i32;
+        gradient_style_region.size = self.gradient_style.requiring_size(app);
+        self.gradient_style.set_region(app, gradient_style_region);
+
+        let mut gradient_end_color_region = region;
+        gradient_end_color_region.position.x = gradient_style_region.end().x + MARGIN_X as i32;
+        gradient_end_color_region.size = self.gradient_end_color.requiring_size(app);
+        self.gradient_end_color
+            .set_region(app, gradient_end_color_region);
+    }
+}
@@ -0,0 +1,246 @@
+use super::{
+    FixedSizeWidget, VariableSizeWidget, Widget, block::BlockWidget, button::ButtonWidget,
+};
+use crate::{
+    app::App,
+    asset::{ButtonKind, IconId, Text},
+    canvas_ext::CanvasExt,
+    color,
+    event::{Event, MouseAction},
+    model::config::{BandEdit, BlendMode, Layer},
+    region_ext::RegionExt,
+};
+use orfail::{OrFail, Result};
+use pagurus::image::Canvas;
+use pagurus::spatial::{Contains, Position, Region, Size};
+
+const MARGIN_X: u32 = 8;
+const MARGIN_Y: u32 = 14;
+const ROW_HEIGHT: u32 = 30;
+const NUMBER_WIDTH: u32 = 40;
+
+/// Lists the layers (the top layer first) and rearranges them.
+#[derive(Debug)]
+pub struct LayersWidget {
+    region: Region,
+    list_region: Region,
+    selected: usize,
+    move_up: BlockWidget<ButtonWidget>,
+    move_down: BlockWidget<ButtonWidget>,
+    insert: BlockWidget<ButtonWidget>,
+    duplicate: BlockWidget<ButtonWidget>,
+    delete: BlockWidget<ButtonWidget>,
+}
+
+impl LayersWidget {
+    pub fn new(app: &App) -> Self {
+        Self {
+            region: Region::default(),
+            list_region: Region::default(),
+            selected: app.models().config.camera.current_layer(app),
+            move_up: BlockWidget::new(
+                "UP".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::GoTop),
+            ),
+            move_down: BlockWidget::new(
+                "DOWN".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::GoBottom),
+            ),
+            insert: BlockWidget::new(
+                "ADD".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Import),
+            ),
+            duplicate: BlockWidget::new(
+                "COPY".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Copy),
+            ),
+            delete: BlockWidget::new(
+                "DELETE".parse().expect("unreachable"),
+                ButtonWidget::new(ButtonKind::Basic, IconId::Cut),
+            ),
+        }
+    }
+
+    fn layer_count(app: &App) -> usize {
+        usize::from(app.models().config.layer.enabled_count())
+    }
+
+    fn row_region(&self, row: usize) -> Region {
+        let mut region = self.list_region;
+        region.position.y += (row as u32 * ROW_HEIGHT) as i32;
+        region.size.height = ROW_HEIGHT;
+        region
+    }
+
+    fn render_layer(&self, app: &App, canvas: &mut Canvas, layer: usize, region: Region) {
+        if layer == self.selected {
+            canvas.fill_rectangle(region.without_margin(2), color::TEXT_BOX_FOCUSED);
+            canvas.draw_rectangle(region.without_margin(2), color::WINDOW_BORDER);
+        } else {
+            canvas.fill_rectangle(region.without_margin(2), color::TEXT_BOX_UNFOCUSED);
+        }
+
+        let mut position = region.position;
+        position.x += NUMBER_WIDTH as i32 - 20;
+        position.y += 8;
+        canvas.draw_number(position, layer as u32 + 1, &app.assets().digits_10x14);
+
+        let attrs = app.models().config.layer_attrs.get(layer);
+        let mut labels = Vec::new();
+        if attrs.hidden {
+            labels.push("HIDDEN");
+        }
+        if attrs.locked {
+            labels.push("LOCKED");
+        }
+        if attrs.blend_mode != BlendMode::Normal {
+            labels.push(attrs.blend_mode.name());
+        }
+        let text: Text = labels.join(" ").parse().expect("unreachable");
+        position.x = region.position.x + NUMBER_WIDTH as i32;
+        canvas
+            .offset(position)
+            .draw_text(&text, &app.assets().alphabet_10x14);
+    }
+
+    fn edit(&mut self, app: &mut App, edit: BandEdit, selected: usize) -> Result<()> {
+        let count = Self::layer_count(app);
+        if !app.models().config.layer.is_enabled()
+            || !edit.is_applicable(count, usize::from(Layer::MAX))
+        {
+            return Ok(());
+        }
+
+        let models = app.models_mut();
+        models
+            .pixel_canvas
+            .edit_layers(&mut models.config, edit)
+            .or_fail()?;
+
+        let count = edit.applied_count(count);
+        self.selected = selected.min(count.saturating_sub(1));
+        app.request_redraw(app.screen_size().to_region());
+        Ok(())
+    }
+}
+
+impl Widget for LayersWidget {
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn render(&self, app: &App, canvas: &mut Canvas) {
+        let count = Self::layer_count(app);
+        for layer in 0..count {
+            self.render_layer(app, canvas, layer, self.row_region(count - 1 - layer));
+        }
+
+        self.move_up.render_if_need(app, canvas);
+        self.move_down.render_if_need(app, canvas);
+        self.insert.render_if_need(app, canvas);
+        self.duplicate.render_if_need(app, canvas);
+        self.delete.render_if_need(app, canvas);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
+        let count = Self::layer_count(app);
+        if let Event::Mouse {
+            action: MouseAction::Up,
+            position,
+            consumed: false,
+            ..
+        } = event
+            && let Some(row) = (0..count).find(|&row| self.row_region(row).contains(position))
+        {
+            self.selected = count - 1 - row;
+            app.request_redraw(self.list_region);
+        }
+
+        let selected = self.selected;
+        self.move_up.handle_event(app, event).or_fail()?;
+        if self.move_up.body_mut().take_clicked(app) {
+            self.edit(app, BandEdit::Swap(selected, selected + 1), selected + 1)
+                .or_fail()?;
+        }
+
+        self.move_down.handle_event(app, event).or_fail()?;
+        if self.move_down.body_mut().take_clicked(app) && selected > 0 {
+            self.edit(app, BandEdit::Swap(selected, selected - 1), selected - 1)
+                .or_fail()?;
+        }
+
+        self.insert.handle_event(app, event).or_fail()?;
+        if self.insert.body_mut().take_clicked(app) {
+            self.edit(app, BandEdit::Insert(selected + 1), selected + 1)
+                .or_fail()?;
+        }
+
+        self.duplicate.handle_event(app, event).or_fail()?;
+        if self.duplicate.body_mut().take_clicked(app) {
+            self.edit(app, BandEdit::Duplicate(selected), selected + 1)
+                .or_fail()?;
+        }
+
+        self.delete.handle_event(app, event).or_fail()?;
+        if self.delete.body_mut().take_clicked(app) && count > 1 {
+            self.edit(app, BandEdit::Delete(selected), selected)
+                .or_fail()?;
+        }
+
+        event.consume_if_contained(self.region);
+        Ok(())
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![
+            &mut self.move_up,
+            &mut self.move_down,
+            &mut self.insert,
+            &mut self.duplicate,
+            &mut self.delete,
+        ]
+    }
+}
+
+impl FixedSizeWidget for LayersWidget {
+    fn requiring_size(&self, app: &App) -> Size {
+        let buttons = [
+            &self.move_up,
+            &self.move_down,
+            &self.insert,
+            &self.duplicate,
+            &self.delete,
+        ];
+        let mut size = Size::from_wh(0, 0);
+        for button in buttons {
+            let button_size = button.requiring_size(app);
+            size.width += button_size.width + MARGIN_X;
+            size.height = size.height.max(button_size.height);
+        }
+        size.width -= MARGIN_X;
+        size.height += ROW_HEIGHT * u32::from(Layer::MAX) + MARGIN_Y;
+        size + MARGIN_X * 2
+    }
+
+    fn set_position(&mut self, app: &App, position: Position) {
+        self.region = Region::new(position, self.requiring_size(app));
+
+        let mut region = self.region.without_margin(MARGIN_X);
+        self.list_region = region;
+        self.list_region.size.height = ROW_HEIGHT * u32::from(Layer::MAX);
+        region.consume_y(self.list_region.size.height + MARGIN_Y);
+
+        for button in [
+            &mut self.move_up,
+            &mut self.move_down,
+            &mut self.insert,
+            &mut self.duplicate,
+            &mut self.delete,
+        ] {
+            let mut button_region = region;
+            button_region.size = button.requiring_size(app);
+            button.set_region(app, button_region);
+            region.position.x = button_region.end().x + MARGIN_X as i32;
+        }
+    }
+}
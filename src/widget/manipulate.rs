@@ -112,9 +112,11 @@ impl ManipulateWidget {
         }
     }
 
-    fn handle_terminate(&mut self, app: &mut App) -> Result<()> {
+    // Returns `false` if the pixels are not applied because of locked layers.
+    fn handle_terminate(&mut self, app: &mut App) -> Result<bool> {
         let config = app.models().config.clone();
-        app.models_mut()
+        let applied = app
+            .models_mut()
             .pixel_canvas
             .erase_and_draw_pixels(
                 &config,
@@ -127,7 +129,7 @@ impl ManipulateWidget {
             )
             .or_fail()?;
 
-        Ok(())
+        Ok(applied)
     }
 
     fn vertical_flip(&mut self, app: &mut App) {
@@ -247,8 +249,8 @@ impl Widget for ManipulateWidget {
                 app.request_redraw(app.screen_size().to_region());
                 return Ok(());
             }
-            if self.tool.is_copy_clicked(app) {
-                self.handle_terminate(app).or_fail()?; // TODO: rename
+            if self.tool.is_copy_clicked(app) && self.handle_terminate(app).or_fail()? {
+                // TODO: rename `handle_terminate()`
                 self.selected_pixels.clear();
 
                 let unit = app.models().config.minimum_pixel_size.get();
@@ -362,8 +364,9 @@ impl Widget for ManipulateWidget {
             app.request_redraw(self.region);
         }
 
-        if self.terminated {
-            self.handle_terminate(app).or_fail()?;
+        if self.terminated && !self.handle_terminate(app).or_fail()? {
+            // The selection is kept so that it can be moved to unlocked layers.
+            self.terminated = false;
         }
 
         Ok(())
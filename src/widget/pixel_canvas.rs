@@ -71,6 +71,44 @@ impl PixelCanvasWidget {
             }
             current.x += i32::from(zoom);
         }
+
+        let config = &app.models().config;
+        let symmetry = config.symmetry;
+        if !symmetry.is_enabled() {
+            return;
+        }
+        let frame_region = config.frame.get_base_region();
+        let full_region = config.frame.get_full_region(config);
+        let frame_size = frame_region.size();
+        let frames = full_region.size().width / frame_size.width;
+        let layers = full_region.size().height / frame_size.height;
+        for frame in 0..frames as i16 {
+            for layer in 0..layers as i16 {
+                let cell = frame_region.shift_x(frame).shift_y(layer);
+                let screen_cell = cell.to_screen_region(app);
+                let (x2, y2) = symmetry.doubled_axes(frame_region, cell.start);
+                if symmetry.horizontal {
+                    let mut position = PixelPosition::from_xy(x2.div_euclid(2), cell.start.y)
+                        .to_screen_position(app);
+                    position.x += i32::from(x2.rem_euclid(2)) * i32::from(zoom) / 2;
+                    canvas.draw_vertical_line(
+                        position,
+                        screen_cell.size.height,
+                        color::SYMMETRY_AXIS,
+                    );
+                }
+                if symmetry.vertical {
+                    let mut position = PixelPosition::from_xy(cell.start.x, y2.div_euclid(2))
+                        .to_screen_position(app);
+                    position.y += i32::from(y2.rem_euclid(2)) * i32::from(zoom) / 2;
+                    canvas.draw_horizontal_line(
+                        position,
+                        screen_cell.size.width,
+                        color::SYMMETRY_AXIS,
+                    );
+                }
+            }
+        }
     }
 
     fn render_frame_edges(&self, app: &App, canvas: &mut Canvas) {
@@ -128,7 +166,10 @@ impl PixelCanvasWidget {
 
     fn render_drawn_pixels(&self, app: &App, canvas: &mut Canvas) {
         let color = app.models().config.color.get();
-        if self.marker_handler.is_neutral() && app.models().tool.tool_kind() != ToolKind::Fill {
+        if self.marker_handler.is_neutral()
+            && app.models().tool.tool_kind() != ToolKind::Fill
+            && !app.models().config.symmetry.is_enabled()
+        {
             let pixel_region = PixelRegion::from_positions(self.marker_handler.marked_pixels(app));
             let region = pixel_region.to_screen_region(app);
             canvas.draw_rectangle(region, color.into());
@@ -138,11 +179,25 @@ impl PixelCanvasWidget {
                 if canvas.drawing_region().intersection(region).is_empty() {
                     continue;
                 }
+                let Some(color) = self.drawing_color(app, pixel_position) else {
+                    continue;
+                };
                 canvas.fill_rectangle(region, color.into());
             }
         }
     }
 
+    // Returns the color to draw at the given marked position (`None` means the position is not drawn).
+    fn drawing_color(&self, app: &App, position: PixelPosition) -> Option<Rgba> {
+        let config = &app.models().config;
+        let color = config.color.get();
+        if let Some((start, end)) = self.marker_handler.gradient_line(position) {
+            let gradient = config.gradient;
+            return Some(gradient.color(start, end, position, color, gradient.end_color));
+        }
+        config.dither.color(position, color)
+    }
+
     fn render_selected_pixels(&self, app: &App, canvas: &mut Canvas) {
         let color = Rgba::new(200, 200, 200, 200); // TODO
         if self.marker_handler.is_neutral() {
@@ -160,6 +215,38 @@ impl PixelCanvasWidget {
         }
     }
 
+    // Draws the pixels of the frames around the current frame (behind the current frame's pixels).
+    fn render_onion_skin(&self, app: &App, canvas: &mut Canvas, pixel_region: PixelRegion) {
+        let config = &app.models().config;
+        let frame_count = config.animation.enabled_frame_count() as i16;
+        if frame_count == 1 {
+            return;
+        }
+
+        let current_frame = config.camera.current_frame(app) as i16;
+        let frame_width = config.frame.get_base_region().size().width as i16;
+        let mut current_region = config.frame.get_full_region(config);
+        current_region.start.x += frame_width * current_frame;
+        current_region.end.x = current_region.start.x + frame_width;
+        let region = current_region.intersection(pixel_region);
+
+        let pixel_canvas = &app.models().pixel_canvas;
+        for offset in config.onion_skin.frame_offsets() {
+            if !(0..frame_count).contains(&(current_frame + offset)) {
+                continue;
+            }
+            for position in region.pixels() {
+                let Some(color) =
+                    pixel_canvas.get_pixel(config, position.move_x(frame_width * offset))
+                else {
+                    continue;
+                };
+                let color = config.onion_skin.apply(color, offset);
+                canvas.fill_rectangle(position.to_screen_region(app), color.into());
+            }
+        }
+    }
+
     fn render_pixels(&self, app: &App, canvas: &mut Canvas) {
         let erasing_pixels = if self.tool.tool_kind() == ToolKind::Erase {
             self.marker_handler
@@ -172,6 +259,10 @@ impl PixelCanvasWidget {
 
         let config = &app.models().config;
         let pixel_region = PixelRegion::from_screen_region(app, canvas.drawing_region());
+        if config.onion_skin.is_enabled() && !app.models().preview_mode {
+            self.render_onion_skin(app, canvas, pixel_region);
+        }
+
         for pixel in app
             .models()
             .pixel_canvas
@@ -235,16 +326,16 @@ impl PixelCanvasWidget {
                 app.request_redraw(app.screen_size().to_region());
             }
             GestureEvent::TwoFingerSwipe { undo } => {
-                let config = app.models().config.clone();
+                let models = app.models_mut();
                 if undo {
-                    app.models_mut()
+                    models
                         .pixel_canvas
-                        .undo_command(&config)
+                        .undo_command(&mut models.config)
                         .or_fail()?;
                 } else {
-                    app.models_mut()
+                    models
                         .pixel_canvas
-                        .redo_command(&config)
+                        .redo_command(&mut models.config)
                         .or_fail()?;
                 }
                 app.enqueue_io_request(IoRequest::Vibrate);
@@ -324,14 +415,14 @@ impl Widget for PixelCanvasWidget {
             let config = app.models().config.clone();
             match self.tool.tool_kind() {
                 ToolKind::Draw | ToolKind::Fill => {
-                    let color = app.models().config.color.get();
                     let pixels = self
                         .marker_handler
                         .marked_pixels(app)
-                        .map(|pos| Pixel::new(pos, color));
+                        .filter_map(|pos| Some(Pixel::new(pos, self.drawing_color(app, pos)?)))
+                        .collect::<Vec<_>>();
                     app.models_mut()
                         .pixel_canvas
-                        .draw_pixels(&config, pixels)
+                        .draw_pixels(&config, pixels.into_iter())
                         .or_fail()?;
                 }
                 ToolKind::Erase => {
@@ -342,8 +433,12 @@ impl Widget for PixelCanvasWidget {
                         .or_fail()?;
                 }
                 ToolKind::Select => {
-                    let target_pixels: HashSet<_> =
-                        self.marker_handler.marked_pixels(app).collect();
+                    // Pixels of locked layers cannot be moved, so they are not selected.
+                    let target_pixels: HashSet<_> = self
+                        .marker_handler
+                        .marked_pixels(app)
+                        .filter(|p| !config.is_locked_position(*p))
+                        .collect();
                     if target_pixels
                         .iter()
                         .any(|p| app.models().pixel_canvas.get_direct_pixel(*p).is_some())
@@ -277,26 +277,38 @@ impl FixedSizeWidget for PreviewFrameWidget {
 #[derive(Debug)]
 struct Playing {
     current_frame: usize,
+    index: usize,
 }
 
 impl Playing {
     fn start(app: &mut App) -> Self {
         let current_frame = app.models().config.camera.current_frame(app);
-        let frame_interval = app.models().config.animation.frame_interval();
-        app.set_timeout(PLAYING_TAG, frame_interval);
-        Self { current_frame }
+        let frames = app.models().config.playing_frames();
+        let index = frames
+            .iter()
+            .position(|&frame| frame == current_frame)
+            .unwrap_or(0);
+        let current_frame = frames[index];
+        let frame_duration = app.models().config.frame_duration(current_frame);
+        app.set_timeout(PLAYING_TAG, frame_duration);
+        Self {
+            current_frame,
+            index,
+        }
     }
 
     fn handle_event(&mut self, app: &mut App, event: &Event, preview_region: Region) -> Result<()> {
         let Event::Timeout(PLAYING_TAG) = event else {
             return Ok(());
         };
-        let frame_interval = app.models().config.animation.frame_interval();
-        app.set_timeout(PLAYING_TAG, frame_interval);
-        self.current_frame += 1;
-        if self.current_frame >= app.models().config.animation.enabled_frame_count() as usize {
-            self.current_frame = 0;
+        let frames = app.models().config.playing_frames();
+        self.index += 1;
+        if self.index >= frames.len() {
+            self.index = 0;
         }
+        self.current_frame = frames[self.index];
+        let frame_duration = app.models().config.frame_duration(self.current_frame);
+        app.set_timeout(PLAYING_TAG, frame_duration);
         app.request_redraw(preview_region);
         Ok(())
     }
@@ -0,0 +1,81 @@
+use super::{FixedSizeWidget, Widget};
+use crate::{
+    app::App,
+    canvas_ext::CanvasExt,
+    color,
+    event::{Event, MouseAction},
+    model::config::ConfigModel,
+    region_ext::RegionExt,
+};
+use pagurus::image::{Canvas, Rgba};
+use pagurus::{
+    Result,
+    spatial::{Contains, Position, Region, Size},
+};
+
+const MARGIN: u32 = 4;
+
+/// Shows a color other than the drawing color (e.g., the second color of the two-color dither)
+/// and swaps it with the drawing color when clicked.
+#[derive(Debug)]
+pub struct SecondColorWidget {
+    region: Region,
+    get: fn(&ConfigModel) -> Rgba,
+    set: fn(&mut ConfigModel, Rgba),
+}
+
+impl SecondColorWidget {
+    pub fn new(get: fn(&ConfigModel) -> Rgba, set: fn(&mut ConfigModel, Rgba)) -> Self {
+        Self {
+            region: Region::default(),
+            get,
+            set,
+        }
+    }
+}
+
+impl Widget for SecondColorWidget {
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn render(&self, app: &App, canvas: &mut Canvas) {
+        let color = (self.get)(&app.models().config);
+        canvas.fill_rectangle(self.region.without_margin(MARGIN), color::WINDOW_BORDER);
+        canvas.fill_rectangle(self.region.without_margin(MARGIN + 2), color.into());
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
+        if let Event::Mouse {
+            action: MouseAction::Up,
+            position,
+            consumed: false,
+            ..
+        } = event
+            && self.region.contains(position)
+        {
+            let config = &mut app.models_mut().config;
+            let color = config.color.get();
+            config.color.set((self.get)(config));
+            (self.set)(config, color);
+            app.request_redraw(app.screen_size().to_region());
+        }
+
+        event.consume_if_contained(self.region);
+        Ok(())
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![]
+    }
+}
+
+impl FixedSizeWidget for SecondColorWidget {
+    fn requiring_size(&self, app: &App) -> Size {
+        Size::square(app.assets().number_box.size().height)
+    }
+
+    fn set_position(&mut self, app: &App, position: Position) {
+        self.region = Region::new(position, self.requiring_size(app));
+    }
+}
@@ -50,6 +50,10 @@ impl SelectBoxWidget {
         self.selected
     }
 
+    pub fn is_reselected(&self) -> bool {
+        self.prev_selected == Some(self.selected)
+    }
+
     pub fn buttons(&self) -> &[ButtonWidget] {
         &self.buttons
     }
@@ -0,0 +1,228 @@
+use super::{FixedSizeWidget, Widget, button::ButtonWidget};
+use crate::{
+    app::App,
+    asset::{ButtonKind, IconId},
+    canvas_ext::CanvasExt,
+    color,
+    event::{Event, MouseAction},
+    model::config::{Animation, BandEdit},
+    region_ext::RegionExt,
+};
+use orfail::{OrFail, Result};
+use pagurus::{
+    image::Canvas,
+    spatial::{Contains, Position, Region, Size},
+};
+
+const MARGIN: u32 = 8;
+const CELL_WIDTH: u32 = 48;
+const VISIBLE_FRAMES: usize = 5;
+
+/// Shows the frames around the current one and inserts, duplicates, deletes or reorders them.
+///
+/// Clicking a frame moves the camera to it, and dragging a frame onto another one moves the frame there.
+#[derive(Debug)]
+pub struct TimelineWidget {
+    region: Region,
+    cells_region: Region,
+    dragging: Option<usize>,
+    insert: ButtonWidget,
+    duplicate: ButtonWidget,
+    delete: ButtonWidget,
+}
+
+impl TimelineWidget {
+    fn frame_count(app: &App) -> usize {
+        usize::from(app.models().config.animation.enabled_frame_count())
+    }
+
+    fn first_visible_frame(app: &App) -> usize {
+        let current = app.models().config.camera.current_frame(app);
+        let frames = Self::frame_count(app);
+        current
+            .saturating_sub(VISIBLE_FRAMES / 2)
+            .min(frames.saturating_sub(VISIBLE_FRAMES))
+    }
+
+    fn cell_region(&self, slot: usize) -> Region {
+        let mut region = self.cells_region;
+        region.position.x += (slot as u32 * CELL_WIDTH) as i32;
+        region.size.width = CELL_WIDTH;
+        region
+    }
+
+    fn frame_at(&self, app: &App, position: Position) -> Option<usize> {
+        let first = Self::first_visible_frame(app);
+        (0..VISIBLE_FRAMES)
+            .find(|&slot| self.cell_region(slot).contains(&position))
+            .map(|slot| first + slot)
+            .filter(|&frame| frame < Self::frame_count(app))
+    }
+
+    fn go_to_frame(app: &mut App, current: usize, frame: usize) {
+        let width = app.models().config.frame.get_base_region().size().width as i32;
+        let delta = Position::from_xy((frame as i32 - current as i32) * width, 0);
+        if delta != Position::ORIGIN {
+            let delta = delta * app.models().config.zoom.get() as u32;
+            app.models_mut().config.camera.r#move(delta);
+            app.request_redraw(app.screen_size().to_region());
+        }
+    }
+
+    fn edit(app: &mut App, edit: BandEdit, next_frame: usize) -> Result<()> {
+        let frames = Self::frame_count(app);
+        if !edit.is_applicable(frames, usize::from(Animation::MAX_FRAME_COUNT)) {
+            return Ok(());
+        }
+
+        let current = app.models().config.camera.current_frame(app);
+        let models = app.models_mut();
+        models
+            .pixel_canvas
+            .edit_frames(&mut models.config, edit)
+            .or_fail()?;
+        app.request_redraw(app.screen_size().to_region());
+
+        Self::go_to_frame(app, current, next_frame);
+        Ok(())
+    }
+
+    fn handle_cell_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
+        let Event::Mouse {
+            action,
+            position,
+            consumed: false,
+            ..
+        } = event
+        else {
+            return Ok(());
+        };
+
+        let frame = self.frame_at(app, *position);
+        match action {
+            MouseAction::Down => {
+                self.dragging = frame;
+            }
+            MouseAction::Up => {
+                let dragging = self.dragging.take();
+                match (dragging, frame) {
+                    (Some(from), Some(to)) if from != to => {
+                        Self::edit(app, BandEdit::Move(from, to), to).or_fail()?;
+                    }
+                    (_, Some(frame)) => {
+                        let current = app.models().config.camera.current_frame(app);
+                        Self::go_to_frame(app, current, frame);
+                    }
+                    _ => {}
+                }
+                app.request_redraw(self.cells_region);
+            }
+            MouseAction::Move => {}
+        }
+        Ok(())
+    }
+}
+
+impl Default for TimelineWidget {
+    fn default() -> Self {
+        Self {
+            region: Region::default(),
+            cells_region: Region::default(),
+            dragging: None,
+            insert: ButtonWidget::new(ButtonKind::Basic, IconId::Import),
+            duplicate: ButtonWidget::new(ButtonKind::Basic, IconId::Copy),
+            delete: ButtonWidget::new(ButtonKind::Basic, IconId::Cut),
+        }
+    }
+}
+
+impl Widget for TimelineWidget {
+    fn region(&self) -> Region {
+        self.region
+    }
+
+    fn render(&self, app: &App, canvas: &mut Canvas) {
+        canvas.fill_rectangle(self.region, color::BUTTONS_BACKGROUND);
+        canvas.draw_rectangle(self.region, color::WINDOW_BORDER);
+
+        let current = app.models().config.camera.current_frame(app);
+        let first = Self::first_visible_frame(app);
+        for frame in (first..Self::frame_count(app)).take(VISIBLE_FRAMES) {
+            let cell = self.cell_region(frame - first).without_margin(2);
+            if frame == current {
+                canvas.fill_rectangle(cell, color::TEXT_BOX_FOCUSED);
+                canvas.draw_rectangle(cell, color::CURRENT_FRAME_EDGE);
+            } else {
+                canvas.fill_rectangle(cell, color::TEXT_BOX_UNFOCUSED);
+            }
+            if self.dragging == Some(frame) {
+                canvas.draw_rectangle(cell.without_margin(2), color::FRAME_EDGE);
+            }
+
+            let mut number_position = cell.position;
+            number_position.x = cell.end().x - 16;
+            number_position.y += (cell.size.height as i32 - 14) / 2;
+            canvas.draw_number(
+                number_position,
+                frame as u32 + 1,
+                &app.assets().digits_10x14,
+            );
+        }
+
+        self.insert.render_if_need(app, canvas);
+        self.duplicate.render_if_need(app, canvas);
+        self.delete.render_if_need(app, canvas);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &mut Event) -> Result<()> {
+        self.handle_cell_event(app, event).or_fail()?;
+
+        let current = app.models().config.camera.current_frame(app);
+        self.insert.handle_event(app, event).or_fail()?;
+        if self.insert.take_clicked(app) {
+            Self::edit(app, BandEdit::Insert(current + 1), current + 1).or_fail()?;
+        }
+
+        self.duplicate.handle_event(app, event).or_fail()?;
+        if self.duplicate.take_clicked(app) {
+            Self::edit(app, BandEdit::Duplicate(current), current + 1).or_fail()?;
+        }
+
+        self.delete.handle_event(app, event).or_fail()?;
+        if self.delete.take_clicked(app) && Self::frame_count(app) > 1 {
+            let next = current.min(Self::frame_count(app) - 2);
+            Self::edit(app, BandEdit::Delete(current), next).or_fail()?;
+        }
+
+        event.consume_if_contained(self.region);
+        Ok(())
+    }
+
+    fn children(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut self.insert, &mut self.duplicate, &mut self.delete]
+    }
+}
+
+impl FixedSizeWidget for TimelineWidget {
+    fn requiring_size(&self, app: &App) -> Size {
+        let button_size = self.insert.requiring_size(app);
+        Size::from_wh(
+            CELL_WIDTH * VISIBLE_FRAMES as u32 + (button_size.width + MARGIN * 2) * 3 + MARGIN * 2,
+            button_size.height + MARGIN * 2,
+        )
+    }
+
+    fn set_position(&mut self, app: &App, position: Position) {
+        self.region = Region::new(position, self.requiring_size(app));
+
+        let mut region = self.region.without_margin(MARGIN);
+        self.cells_region = region;
+        self.cells_region.size.width = CELL_WIDTH * VISIBLE_FRAMES as u32;
+        region.position.x = self.cells_region.end().x + MARGIN as i32;
+
+        for button in [&mut self.insert, &mut self.duplicate, &mut self.delete] {
+            button.set_position(app, region.position);
+            region.position.x += (button.requiring_size(app).width + MARGIN * 2) as i32;
+        }
+    }
+}
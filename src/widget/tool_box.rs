@@ -39,6 +39,24 @@ impl ToolBoxWidget {
             .or_fail()
     }
 
+    fn handle_sub_tool_switch(&mut self, app: &mut App) -> Result<()> {
+        if self.tools.is_reselected() {
+            app.models_mut().tool.switch_sub_tool();
+        }
+        Ok(())
+    }
+
+    fn update_icons(&mut self, app: &mut App) -> Result<()> {
+        for button in self.tools.buttons_mut() {
+            let kind = ToolKind::from_icon(button.icon()).or_fail()?;
+            let icon = app.models().tool.tool_icon(kind);
+            if button.icon() != icon {
+                button.set_icon(app, icon);
+            }
+        }
+        Ok(())
+    }
+
     fn handle_key_event(&mut self, app: &mut App, event: &mut Event) -> Result<bool> {
         let Event::Key { event, consumed } = event else {
             return Ok(false);
@@ -62,7 +80,11 @@ impl ToolBoxWidget {
                 return Ok(false);
             }
         };
-        self.tools.select(app, index).or_fail()?;
+        if index == self.tools.selected() {
+            app.models_mut().tool.switch_sub_tool();
+        } else {
+            self.tools.select(app, index).or_fail()?;
+        }
         *consumed = true;
         Ok(true)
     }
@@ -72,7 +94,7 @@ impl Default for ToolBoxWidget {
     fn default() -> Self {
         let mut buttons = vec![
             ButtonWidget::new(ButtonKind::Basic, IconId::Pick),
-            ButtonWidget::new(ButtonKind::Basic, IconId::PenStroke),
+            ButtonWidget::new(ButtonKind::BasicPressed, IconId::PenStroke),
             ButtonWidget::new(ButtonKind::Basic, IconId::Bucket),
             ButtonWidget::new(ButtonKind::Basic, IconId::Erase),
             ButtonWidget::new(ButtonKind::Basic, IconId::Lasso),
@@ -80,10 +102,9 @@ impl Default for ToolBoxWidget {
         ];
 
         buttons[0].set_disabled_callback(|app| app.models().tool.current == ToolKind::Pick);
-        buttons[1].set_disabled_callback(|app| app.models().tool.current == ToolKind::Draw);
+        // The buttons of the tools having sub tools (DRAW, ERASE and SELECT) are kept enabled
+        // so that clicking them again switches the sub tool.
         buttons[2].set_disabled_callback(|app| app.models().tool.current == ToolKind::Fill);
-        buttons[3].set_disabled_callback(|app| app.models().tool.current == ToolKind::Erase);
-        buttons[4].set_disabled_callback(|app| app.models().tool.current == ToolKind::Select);
         buttons[5].set_disabled_callback(|app| app.models().tool.current == ToolKind::Move);
 
         Self {
@@ -109,6 +130,7 @@ impl Widget for ToolBoxWidget {
         if !self.handle_key_event(app, event).or_fail()? {
             self.tools.handle_event(app, event).or_fail()?;
         }
+        self.handle_sub_tool_switch(app).or_fail()?;
         self.handle_tool_change(app).or_fail()?;
         event.consume_if_contained(self.region);
         Ok(())
@@ -126,6 +148,7 @@ impl Widget for ToolBoxWidget {
             self.tools.select(app, i).or_fail()?;
             self.handle_tool_change(app).or_fail()?;
         }
+        self.update_icons(app).or_fail()?;
 
         for child in self.children() {
             child.handle_event_after(app).or_fail()?;
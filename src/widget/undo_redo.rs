@@ -13,7 +13,6 @@ use pagurus::spatial::{Position, Region, Size};
 use pagurus::{event::Key, image::Canvas};
 
 const MARGIN: u32 = 8;
-const MAX_UNDO: usize = 100;
 
 #[derive(Debug)]
 pub struct UndoRedoWidget {
@@ -36,17 +35,17 @@ impl UndoRedoWidget {
         };
         match event.key {
             Key::Char('z') if event.ctrl => {
-                let config = app.models().config.clone();
-                app.models_mut()
+                let models = app.models_mut();
+                models
                     .pixel_canvas
-                    .undo_command(&config)
+                    .undo_command(&mut models.config)
                     .or_fail()?;
             }
             Key::Char('y') if event.ctrl => {
-                let config = app.models().config.clone();
-                app.models_mut()
+                let models = app.models_mut();
+                models
                     .pixel_canvas
-                    .redo_command(&config)
+                    .redo_command(&mut models.config)
                     .or_fail()?;
             }
             _ => {
@@ -101,20 +100,20 @@ impl Widget for UndoRedoWidget {
 
         self.redo.handle_event(app, event).or_fail()?;
         if self.redo.take_clicked(app) {
-            let config = app.models().config.clone();
-            app.models_mut()
+            let models = app.models_mut();
+            models
                 .pixel_canvas
-                .redo_command(&config)
+                .redo_command(&mut models.config)
                 .or_fail()?;
             self.request_redraw_dirty_canvas_region(app);
         }
 
         self.undo.handle_event(app, event).or_fail()?;
         if self.undo.take_clicked(app) {
-            let config = app.models().config.clone();
-            app.models_mut()
+            let models = app.models_mut();
+            models
                 .pixel_canvas
-                .undo_command(&config)
+                .undo_command(&mut models.config)
                 .or_fail()?;
             self.request_redraw_dirty_canvas_region(app);
         }
@@ -123,17 +122,6 @@ impl Widget for UndoRedoWidget {
         Ok(())
     }
 
-    fn handle_event_after(&mut self, app: &mut App) -> Result<()> {
-        let max_undo = MAX_UNDO;
-        while app.models().pixel_canvas.command_log_tail() > max_undo {
-            app.models_mut().pixel_canvas.forget_oldest_command();
-        }
-        for child in self.children() {
-            child.handle_event_after(app).or_fail()?;
-        }
-        Ok(())
-    }
-
     fn children(&mut self) -> Vec<&mut dyn Widget> {
         vec![&mut self.redo, &mut self.undo]
     }
@@ -2,8 +2,11 @@ use crate::{app::App, event::Event};
 use pagurus::image::Canvas;
 use pagurus::{Result, spatial::Region};
 
+pub mod animation_config;
 pub mod color_selector;
 pub mod config;
+pub mod drawing_config;
+pub mod layers;
 pub mod main;
 pub mod widget;
 
@@ -7,6 +7,7 @@ use crate::{
     widget::{
         FixedSizeWidget, VariableSizeWidget, Widget, bottom_bar::BottomBarWidget,
         pixel_canvas::PixelCanvasWidget, preview::PreviewWidget, side_bar::SideBarWidget,
+        timeline::TimelineWidget,
     },
 };
 use orfail::{OrFail, Result};
@@ -20,6 +21,7 @@ pub struct MainWindow {
     preview: PreviewWidget,
     side_bar: SideBarWidget,
     bottom_bar: BottomBarWidget,
+    timeline: TimelineWidget,
 }
 
 impl MainWindow {
@@ -50,6 +52,9 @@ impl Window for MainWindow {
         if !preview_mode {
             self.side_bar.render_if_need(app, canvas);
             self.bottom_bar.render_if_need(app, canvas);
+            if app.models().config.animation.is_enabled() {
+                self.timeline.render_if_need(app, canvas);
+            }
         }
         canvas.draw_rectangle(self.region(), color::WINDOW_BORDER);
     }
@@ -74,6 +79,17 @@ impl Window for MainWindow {
         self.side_bar.set_region(app, self.region());
         self.bottom_bar.set_region(app, self.region());
 
+        // The timeline is placed above the bottom bar and on the right of the side bar
+        // so that it does not overlap the zoom buttons on short screens.
+        let timeline_margin = 16;
+        let timeline_position = Position::from_xy(
+            self.side_bar.region().end().x + timeline_margin,
+            self.bottom_bar.region().position.y
+                - self.timeline.requiring_size(app).height as i32
+                - timeline_margin,
+        );
+        self.timeline.set_position(app, timeline_position);
+
         Ok(())
     }
 
@@ -88,17 +104,22 @@ impl Window for MainWindow {
         self.pixel_canvas.handle_event_before(app).or_fail()?;
         self.side_bar.handle_event_before(app).or_fail()?;
         self.bottom_bar.handle_event_before(app).or_fail()?;
+        self.timeline.handle_event_before(app).or_fail()?;
         self.preview.handle_event_before(app).or_fail()?;
 
         if !self.pixel_canvas.is_operating() {
             self.side_bar.handle_event(app, event).or_fail()?;
             self.bottom_bar.handle_event(app, event).or_fail()?;
+            if app.models().config.animation.is_enabled() {
+                self.timeline.handle_event(app, event).or_fail()?;
+            }
             self.preview.handle_event(app, event).or_fail()?;
         }
 
         self.pixel_canvas.handle_event(app, event).or_fail()?;
 
         self.preview.handle_event_after(app).or_fail()?;
+        self.timeline.handle_event_after(app).or_fail()?;
         self.bottom_bar.handle_event_after(app).or_fail()?;
         self.side_bar.handle_event_after(app).or_fail()?;
         self.pixel_canvas.handle_event_after(app).or_fail()?;
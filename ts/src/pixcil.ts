@@ -28,7 +28,9 @@ type MessageData =
   | { type: "setWorkspace"; requestId: number; body: Uint8Array }
   | { type: "getWorkspace"; requestId: number }
   | { type: "notifyInputSize"; requestId: number; body: { id: number; size: string } }
-  | { type: "notifyInputNumber"; requestId: number; body: { id: number; number: string } };
+  | { type: "notifyInputNumber"; requestId: number; body: { id: number; number: string } }
+  | { type: "notifyInputText"; requestId: number; body: { id: number; text: string } }
+  | { type: "notifyInputCancel"; requestId: number; body: { id: number } };
 
 class App {
   private game: Game;
@@ -94,6 +96,18 @@ class App {
             this.game.command(this.system, "notifyInputSize", inputJsonBytes);
           }
           break;
+        case "notifyInputText":
+          {
+            const inputJsonBytes = new TextEncoder().encode(JSON.stringify(msg.data.body));
+            this.game.command(this.system, "notifyInputText", inputJsonBytes);
+          }
+          break;
+        case "notifyInputCancel":
+          {
+            const inputJsonBytes = new TextEncoder().encode(JSON.stringify(msg.data.body));
+            this.game.command(this.system, "notifyInputCancel", inputJsonBytes);
+          }
+          break;
       }
     } catch (error) {
       console.warn(error);
@@ -196,8 +210,11 @@ class App {
 
       type RequestJson = "saveWorkspace"
           | "loadWorkspace"
+          | "exportGif"
+          | "importSpriteSheet"
           | { inputNumber: { id: number } }
           | { inputSize: { id: number } }
+          | { inputText: { id: number; text: string; error: string | null } }
           | "vibrate";
 
     const requestBytes = this.game.query(this.system, "nextIoRequest");
@@ -210,6 +227,12 @@ class App {
         case "loadWorkspace":
           this.loadWorkspace();
           break;
+        case "exportGif":
+          this.exportGif();
+          break;
+        case "importSpriteSheet":
+          this.importSpriteSheet();
+          break;
         case "vibrate":
           if ("vibrate" in window.navigator) {
             window.navigator.vibrate(50);
@@ -222,6 +245,9 @@ class App {
           } else if ("inputSize" in requestJson) {
             const inputId = requestJson.inputSize.id;
             this.parent.postMessage({ type: "inputSize", inputId });
+          } else if ("inputText" in requestJson) {
+            const { id: inputId, text, error } = requestJson.inputText;
+            this.parent.postMessage({ type: "inputText", inputId, text, error });
           }
       }
     }
@@ -243,7 +269,44 @@ class App {
     element.click();
   }
 
+  private exportGif() {
+    const name = prompt("Please input your GIF file name", this.generateWorkspaceName());
+    if (!name) {
+      return;
+    }
+
+    const data = this.game.query(this.system, "workspaceGif");
+    const blob = new Blob([data], { type: "image/gif" });
+    const element = document.createElement("a");
+    element.download = name + ".gif";
+    element.href = URL.createObjectURL(blob);
+
+    element.click();
+  }
+
   private loadWorkspace() {
+    this.selectPngFile((data) => {
+      try {
+        this.game.command(this.system, "loadWorkspace", data);
+      } catch (e) {
+        console.warn(e);
+        alert("Failed to load workspace file");
+      }
+    });
+  }
+
+  private importSpriteSheet() {
+    this.selectPngFile((data) => {
+      try {
+        this.game.command(this.system, "importSpriteSheet", data);
+      } catch (e) {
+        console.warn(e);
+        alert("Failed to import sprite sheet file");
+      }
+    });
+  }
+
+  private selectPngFile(onSelected: (data: Uint8Array) => void) {
     const input = document.createElement("input");
     input.setAttribute("type", "file");
     input.setAttribute("accept", "image/png");
@@ -262,12 +325,7 @@ class App {
       const file = files[0];
 
       const data = new Uint8Array(await file.arrayBuffer());
-      try {
-        this.game.command(this.system, "loadWorkspace", data);
-      } catch (e) {
-        console.warn(e);
-        alert("Failed to load workspace file");
-      }
+      onSelected(data);
     };
     input.oncancel = async () => {
       document.body.removeChild(input);
@@ -383,6 +383,44 @@ export class PngEditorProvider
             }
           });
         break;
+      case "inputSize":
+        vscode.window
+          .showInputBox({
+            title: "Please input a {size} or {width}x{height}",
+            validateInput: (param) => {
+              var regex = /^\d+(x\d+)?$/;
+              return regex.test(param) ? null : "Not a size";
+            },
+          })
+          .then((value) => {
+            if (value) {
+              this.postMessage(webviewPanel, "notifyInputSize", {
+                id: message.inputId,
+                number: value,
+              });
+            } else {
+              this.postMessage(webviewPanel, "notifyInputCancel", {
+                id: message.inputId,
+              });
+            }
+          });
+        break;
+      case "inputText":
+        vscode.window
+          .showInputBox({
+            title: "Please input a text",
+            value: message.text || "",
+            prompt: message.error || undefined,
+          })
+          .then((value) => {
+            if (value) {
+              this.postMessage(webviewPanel, "notifyInputText", {
+                id: message.inputId,
+                text: value,
+              });
+            }
+          });
+        break;
       case "errorResponse": {
         const callback = this._callbacks.get(message.requestId);
         if (callback !== undefined) {